@@ -0,0 +1,160 @@
+//! Benchmark example with 5,000 nodes.
+//!
+//! Drag any node to see how long each drag update takes to reach the screen: the time
+//! from the drag event until the next animation frame starts, which includes rendering
+//! the update and applying it to the page. All elements are rendered unless "Only render
+//! visible elements" is ticked.
+
+use dioxus::core::{current_scope_id, Runtime};
+use dioxus::prelude::*;
+use dioxus_flow::components::controls::CONTROLS_STYLES;
+use dioxus_flow::components::flow::FLOW_STYLES;
+use dioxus_flow::hooks::FlowState;
+use dioxus_flow::prelude::*;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+
+const COLUMNS: usize = 100;
+const ROWS: usize = 50;
+
+fn main() {
+    dioxus::launch(App);
+}
+
+/// Grid of nodes, each connected to its right and bottom neighbour.
+fn build_flow() -> (Vec<Node<()>>, Vec<Edge>) {
+    let mut nodes = Vec::with_capacity(COLUMNS * ROWS);
+    let mut edges = Vec::new();
+    for row in 0..ROWS {
+        for column in 0..COLUMNS {
+            let id = format!("{row}-{column}");
+            nodes.push(
+                Node::new(id.clone(), column as f64 * 200.0, row as f64 * 100.0)
+                    .with_label(format!("Node {}", row * COLUMNS + column + 1)),
+            );
+            if column + 1 < COLUMNS {
+                let right = format!("{row}-{}", column + 1);
+                edges.push(Edge::new(format!("{id}-{right}"), id.clone(), right));
+            }
+            if row + 1 < ROWS {
+                let below = format!("{}-{column}", row + 1);
+                edges.push(Edge::new(format!("{id}-{below}"), id.clone(), below));
+            }
+        }
+    }
+    (nodes, edges)
+}
+
+fn now() -> f64 {
+    web_sys::window()
+        .and_then(|w| w.performance())
+        .map(|p| p.now())
+        .unwrap_or(0.0)
+}
+
+/// Call `callback` at the start of the next animation frame.
+fn on_next_frame(callback: impl FnOnce() + 'static) {
+    // Frame callbacks arrive outside of the renderer, so re-enter the current scope
+    let runtime = Runtime::current();
+    let scope = current_scope_id();
+    let callback = Closure::once_into_js(move || runtime.in_scope(scope, callback));
+    if let Some(window) = web_sys::window() {
+        let _ = window.request_animation_frame(callback.unchecked_ref());
+    }
+}
+
+#[component]
+fn App() -> Element {
+    let state: Signal<FlowState<()>> = use_signal(|| {
+        let (nodes, edges) = build_flow();
+        FlowState::with_nodes_and_edges(nodes, edges)
+    });
+    let only_visible = use_signal(|| false);
+    // Moving average of the time from a drag update to the next frame
+    let mut average_ms = use_signal(|| 0.0);
+
+    rsx! {
+        style { "{FLOW_STYLES}" }
+        style { "{CONTROLS_STYLES}" }
+        style { r#"
+            body, html, #main {{
+                margin: 0;
+                padding: 0;
+                width: 100%;
+                height: 100vh;
+                font-family: sans-serif;
+            }}
+            .flow-wrapper {{
+                position: relative;
+                width: 100%;
+                height: 100vh;
+            }}
+            .stats {{
+                position: absolute;
+                top: 10px;
+                left: 10px;
+                z-index: 10;
+                padding: 8px 12px;
+                background: white;
+                border: 1px solid #e2e8f0;
+                border-radius: 6px;
+                font-size: 13px;
+                line-height: 1.6;
+            }}
+        "# }
+
+        div {
+            class: "flow-wrapper",
+            Stats { state: state, average_ms: average_ms, only_visible: only_visible }
+            Flow {
+                state: state,
+                only_render_visible_elements: *only_visible.read(),
+                level_of_detail: LevelOfDetail::default(),
+                on_node_drag_start: move |_| average_ms.set(0.0),
+                on_node_drag: move |_| {
+                    let start = now();
+                    on_next_frame(move || {
+                        let elapsed = now() - start;
+                        let average = *average_ms.peek();
+                        let average = if average == 0.0 {
+                            elapsed
+                        } else {
+                            average * 0.9 + elapsed * 0.1
+                        };
+                        average_ms.set(average);
+                    });
+                },
+                Background {}
+                Controls { state: state }
+            }
+        }
+    }
+}
+
+/// Counters and settings, kept out of `App` so that updating them doesn't re-render the flow.
+#[component]
+fn Stats(
+    state: Signal<FlowState<()>>,
+    average_ms: Signal<f64>,
+    mut only_visible: Signal<bool>,
+) -> Element {
+    let node_count = state.peek().nodes.len();
+    let edge_count = state.peek().edges.len();
+    let average = *average_ms.read();
+
+    rsx! {
+        div {
+            class: "stats",
+            div { "{node_count} nodes, {edge_count} edges" }
+            div { "Drag update to next frame: {average:.1} ms" }
+            label {
+                input {
+                    r#type: "checkbox",
+                    checked: *only_visible.read(),
+                    onchange: move |evt: FormEvent| only_visible.set(evt.checked()),
+                }
+                " Only render visible elements"
+            }
+        }
+    }
+}
@@ -105,7 +105,7 @@ fn App() -> Element {
             100.0 + (count as f64 * 50.0) % 400.0,
             100.0 + (count as f64 * 30.0) % 300.0,
         )
-        .with_label(&format!("node{}", count))
+        .with_label(format!("node{}", count))
         .with_data(NodeData::new(
             &format!("Node {}", count),
             "Dynamically added",
@@ -120,8 +120,7 @@ fn App() -> Element {
     };
 
     let fit_view = move |_| {
-        // Reset viewport to default
-        state.write().set_viewport(Viewport::default());
+        state.write().fit_view_with_options(&FitViewOptions::default());
     };
 
     rsx! {
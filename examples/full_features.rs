@@ -34,6 +34,7 @@ enum TaskStatus {
     Done,
 }
 
+#[allow(dead_code)]
 impl TaskStatus {
     fn class(&self) -> &'static str {
         match self {
@@ -213,6 +214,10 @@ fn App() -> Element {
                 // Main flow
                 Flow {
                     state: state,
+                    helper_lines: true,
+                    fit_view_on_init: true,
+                    only_render_visible_elements: true,
+                    align_shortcuts: true,
                     on_node_click: move |id: String| {
                         web_sys::console::log_1(&format!("Clicked: {}", id).into());
                     },
@@ -227,6 +232,7 @@ fn App() -> Element {
                     position: ControlsPosition::BottomLeft,
                     show_fit_view: true,
                     show_interactive: true,
+                    show_arrange: true,
                 }
 
                 // Minimap
@@ -19,6 +19,7 @@ struct JobData {
     status: JobStatus,
 }
 
+#[allow(dead_code)]
 #[derive(Clone, PartialEq, Default)]
 enum JobStatus {
     #[default]
@@ -238,7 +239,7 @@ fn App() -> Element {
         current_step.set(0);
         // Reset all to pending
         let mut s = state.write();
-        for node in &mut s.nodes {
+        for node in s.nodes_mut().iter_mut() {
             node.data.status = JobStatus::Pending;
             node.node_type = "job-pending".to_string();
         }
@@ -253,13 +254,14 @@ fn App() -> Element {
 
             // Mark previous as success
             if step > 0 {
-                if let Some(prev_node) = s.nodes.iter_mut().find(|n| n.id == job_order[step - 1]) {
+                let previous = job_order[step - 1];
+                if let Some(prev_node) = s.nodes_mut().iter_mut().find(|n| n.id == previous) {
                     prev_node.data.status = JobStatus::Success;
                     prev_node.node_type = "job-success".to_string();
                 }
                 // For parallel jobs (lint and test both at step 0 effectively)
                 if step == 2 {
-                    if let Some(node) = s.nodes.iter_mut().find(|n| n.id == "test") {
+                    if let Some(node) = s.nodes_mut().iter_mut().find(|n| n.id == "test") {
                         node.data.status = JobStatus::Success;
                         node.node_type = "job-success".to_string();
                     }
@@ -267,13 +269,13 @@ fn App() -> Element {
             }
 
             // Mark current as running
-            if let Some(node) = s.nodes.iter_mut().find(|n| n.id == job_order[step]) {
+            if let Some(node) = s.nodes_mut().iter_mut().find(|n| n.id == job_order[step]) {
                 node.data.status = JobStatus::Running;
                 node.node_type = "job-running".to_string();
             }
             // Handle parallel (lint and test)
             if step == 0 {
-                if let Some(node) = s.nodes.iter_mut().find(|n| n.id == "test") {
+                if let Some(node) = s.nodes_mut().iter_mut().find(|n| n.id == "test") {
                     node.data.status = JobStatus::Running;
                     node.node_type = "job-running".to_string();
                 }
@@ -285,7 +287,7 @@ fn App() -> Element {
                 .map(|n| (n.id.clone(), n.data.status.clone()))
                 .collect();
 
-            for edge in &mut s.edges {
+            for edge in s.edges_mut().iter_mut() {
                 if let Some(status) = node_statuses.get(&edge.source) {
                     edge.animated = *status == JobStatus::Success;
                     if *status == JobStatus::Success {
@@ -298,11 +300,11 @@ fn App() -> Element {
         } else {
             // Mark last as success
             let mut s = state.write();
-            if let Some(node) = s.nodes.iter_mut().find(|n| n.id == "deploy-prod") {
+            if let Some(node) = s.nodes_mut().iter_mut().find(|n| n.id == "deploy-prod") {
                 node.data.status = JobStatus::Success;
                 node.node_type = "job-success".to_string();
             }
-            for edge in &mut s.edges {
+            for edge in s.edges_mut().iter_mut() {
                 edge.animated = false;
                 edge.stroke = "#22c55e".to_string();
             }
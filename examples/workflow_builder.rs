@@ -245,6 +245,7 @@ fn App() -> Element {
     rsx! {
         style { "{FLOW_STYLES}" }
         style { "{CONTROLS_STYLES}" }
+        style { "{NODE_RESIZER_STYLES}" }
         style { "{WORKFLOW_BUILDER_STYLES}" }
 
         div {
@@ -332,7 +333,8 @@ fn App() -> Element {
 
                     Flow {
                         state: state,
-                        node_render: Callback::new(move |node: Node<WorkflowNodeData>| {
+                        level_of_detail: LevelOfDetail::default(),
+                        node_render_with_detail: Callback::new(move |(node, detail): (Node<WorkflowNodeData>, DetailLevel)| {
                             let data = &node.data;
                             let icon = data.node_type.icon();
                             let title = &data.title;
@@ -341,34 +343,42 @@ fn App() -> Element {
                             let node_type = data.node_type;
 
                             rsx! {
+                                NodeResizer {
+                                    state: state,
+                                    node_id: node.id.clone(),
+                                    visible: node.selected && detail == DetailLevel::Full,
+                                }
                                 div {
                                     class: "node-header",
                                     span { class: "node-icon", "{icon}" }
                                     span { class: "node-title", "{title}" }
                                 }
-                                // Different rendering based on node type
-                                match node_type {
-                                    // Decision nodes: show condition in a code box
-                                    WorkflowNodeType::Decision => rsx! {
-                                        if !code_preview.is_empty() {
-                                            div { class: "node-code-box", "{code_preview}" }
-                                        }
-                                    },
-                                    // Execution nodes: show language badge + code preview
-                                    WorkflowNodeType::Execution | WorkflowNodeType::TryCatch | WorkflowNodeType::Http => rsx! {
-                                        if !subtitle.is_empty() {
-                                            span { class: "node-badge", "{subtitle}" }
-                                        }
-                                        if !code_preview.is_empty() {
-                                            div { class: "node-code", "{code_preview}" }
-                                        }
-                                    },
-                                    // Join/Split/Loop nodes: show mode badge
-                                    _ => rsx! {
-                                        if !subtitle.is_empty() {
-                                            span { class: "node-badge", "{subtitle}" }
-                                        }
-                                    },
+                                // Zoomed out, only the header is drawn
+                                if detail == DetailLevel::Full {
+                                    // Different rendering based on node type
+                                    match node_type {
+                                        // Decision nodes: show condition in a code box
+                                        WorkflowNodeType::Decision => rsx! {
+                                            if !code_preview.is_empty() {
+                                                div { class: "node-code-box", "{code_preview}" }
+                                            }
+                                        },
+                                        // Execution nodes: show language badge + code preview
+                                        WorkflowNodeType::Execution | WorkflowNodeType::TryCatch | WorkflowNodeType::Http => rsx! {
+                                            if !subtitle.is_empty() {
+                                                span { class: "node-badge", "{subtitle}" }
+                                            }
+                                            if !code_preview.is_empty() {
+                                                div { class: "node-code", "{code_preview}" }
+                                            }
+                                        },
+                                        // Join/Split/Loop nodes: show mode badge
+                                        _ => rsx! {
+                                            if !subtitle.is_empty() {
+                                                span { class: "node-badge", "{subtitle}" }
+                                            }
+                                        },
+                                    }
                                 }
                             }
                         }),
@@ -763,6 +773,8 @@ body, html, #main {
 
 .dioxus-flow-node-selected {
     box-shadow: 0 4px 20px rgba(0, 0, 0, 0.4), 0 0 0 2px #6366f1 !important;
+    /* Let the resize grips stick out */
+    overflow: visible;
 }
 
 /* Handle Styles */
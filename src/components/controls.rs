@@ -1,7 +1,9 @@
 //! Controls component for zoom and fit operations.
 
-use crate::hooks::FlowState;
-use crate::types::Viewport;
+use crate::hooks::{
+    animate_viewport, use_zoom_range_props, FlowState, DEFAULT_MAX_ZOOM, DEFAULT_MIN_ZOOM,
+};
+use crate::types::{DistributeDirection, FitViewOptions, NodeAlignment, Viewport};
 use dioxus::prelude::*;
 
 /// Controls position on the screen.
@@ -31,14 +33,22 @@ pub struct ControlsProps<T: Clone + PartialEq + 'static> {
     /// Whether to show fit view button.
     #[props(default = true)]
     pub show_fit_view: bool,
+    /// Options for the fit view button.
+    #[props(default)]
+    pub fit_view_options: FitViewOptions,
     /// Whether to show lock/interactive toggle button.
     #[props(default = false)]
     pub show_interactive: bool,
-    /// Minimum zoom level.
-    #[props(default = 0.1)]
+    /// Whether to show buttons that align and distribute the selected nodes.
+    #[props(default = false)]
+    pub show_arrange: bool,
+    /// Minimum zoom level. Sets the flow state's zoom range, like `Flow`'s prop of the
+    /// same name, unless both limits are left at their defaults.
+    #[props(default = DEFAULT_MIN_ZOOM)]
     pub min_zoom: f64,
-    /// Maximum zoom level.
-    #[props(default = 4.0)]
+    /// Maximum zoom level. Sets the flow state's zoom range, like `Flow`'s prop of the
+    /// same name, unless both limits are left at their defaults.
+    #[props(default = DEFAULT_MAX_ZOOM)]
     pub max_zoom: f64,
     /// Zoom step amount.
     #[props(default = 0.2)]
@@ -64,23 +74,15 @@ pub fn Controls<T: Clone + Default + PartialEq + 'static>(props: ControlsProps<T
         ControlsPosition::BottomRight => "bottom: 10px; right: 10px;",
     };
 
-    let min_zoom = props.min_zoom;
-    let max_zoom = props.max_zoom;
+    use_zoom_range_props(state, props.min_zoom, props.max_zoom);
     let zoom_step = props.zoom_step;
-    let on_viewport_change = props.on_viewport_change.clone();
-    let on_interactive_change = props.on_interactive_change.clone();
+    let on_viewport_change = props.on_viewport_change;
+    let on_interactive_change = props.on_interactive_change;
 
     let zoom_in = move |_| {
         let mut s = state.write();
-        let old_zoom = s.viewport.zoom;
-        let new_zoom = (old_zoom + zoom_step).min(max_zoom);
-
-        // Zoom around center (assuming 800x600 container)
-        let center_x = 400.0;
-        let center_y = 300.0;
-        s.viewport.x = center_x - (center_x - s.viewport.x) * new_zoom / old_zoom;
-        s.viewport.y = center_y - (center_y - s.viewport.y) * new_zoom / old_zoom;
-        s.viewport.zoom = new_zoom;
+        // Zoom around the centre of the canvas
+        s.zoom_by(zoom_step);
 
         if let Some(handler) = &on_viewport_change {
             handler.call(s.viewport);
@@ -89,28 +91,31 @@ pub fn Controls<T: Clone + Default + PartialEq + 'static>(props: ControlsProps<T
 
     let zoom_out = move |_| {
         let mut s = state.write();
-        let old_zoom = s.viewport.zoom;
-        let new_zoom = (old_zoom - zoom_step).max(min_zoom);
-
-        let center_x = 400.0;
-        let center_y = 300.0;
-        s.viewport.x = center_x - (center_x - s.viewport.x) * new_zoom / old_zoom;
-        s.viewport.y = center_y - (center_y - s.viewport.y) * new_zoom / old_zoom;
-        s.viewport.zoom = new_zoom;
+        s.zoom_by(-zoom_step);
 
         if let Some(handler) = &on_viewport_change {
             handler.call(s.viewport);
         }
     };
 
+    let fit_view_options = props.fit_view_options.clone();
     let fit_view = move |_| {
-        // Reset to default view - in a real implementation would calculate bounds
-        state.write().set_viewport(Viewport::default());
+        let Some(target) = state.read().fit_view_viewport(&fit_view_options) else {
+            return;
+        };
+        animate_viewport(state, target, fit_view_options.duration);
         if let Some(handler) = &on_viewport_change {
-            handler.call(Viewport::default());
+            handler.call(target);
         }
     };
 
+    let mut align = move |alignment: NodeAlignment| {
+        state.write().align_selected_nodes(alignment);
+    };
+    let mut distribute = move |direction: DistributeDirection| {
+        state.write().distribute_selected_nodes(direction);
+    };
+
     let toggle_interactive = move |_| {
         let new_state = !*is_interactive.read();
         is_interactive.set(new_state);
@@ -182,6 +187,140 @@ pub fn Controls<T: Clone + Default + PartialEq + 'static>(props: ControlsProps<T
                 }
             }
 
+            if props.show_arrange {
+                div {
+                    class: "dioxus-flow-controls-group",
+                    button {
+                        class: "dioxus-flow-controls-button",
+                        onclick: move |_| align(NodeAlignment::Left),
+                        title: "Align Left",
+                        svg {
+                            width: "16",
+                            height: "16",
+                            view_box: "0 0 24 24",
+                            fill: "none",
+                            stroke: "currentColor",
+                            stroke_width: "2",
+                            line { x1: "4", y1: "3", x2: "4", y2: "21" }
+                            rect { x: "8", y: "6", width: "12", height: "4" }
+                            rect { x: "8", y: "14", width: "7", height: "4" }
+                        }
+                    }
+                    button {
+                        class: "dioxus-flow-controls-button",
+                        onclick: move |_| align(NodeAlignment::Center),
+                        title: "Align Center",
+                        svg {
+                            width: "16",
+                            height: "16",
+                            view_box: "0 0 24 24",
+                            fill: "none",
+                            stroke: "currentColor",
+                            stroke_width: "2",
+                            line { x1: "12", y1: "3", x2: "12", y2: "21" }
+                            rect { x: "6", y: "6", width: "12", height: "4" }
+                            rect { x: "8", y: "14", width: "8", height: "4" }
+                        }
+                    }
+                    button {
+                        class: "dioxus-flow-controls-button",
+                        onclick: move |_| align(NodeAlignment::Right),
+                        title: "Align Right",
+                        svg {
+                            width: "16",
+                            height: "16",
+                            view_box: "0 0 24 24",
+                            fill: "none",
+                            stroke: "currentColor",
+                            stroke_width: "2",
+                            line { x1: "20", y1: "3", x2: "20", y2: "21" }
+                            rect { x: "4", y: "6", width: "12", height: "4" }
+                            rect { x: "9", y: "14", width: "7", height: "4" }
+                        }
+                    }
+                    button {
+                        class: "dioxus-flow-controls-button",
+                        onclick: move |_| align(NodeAlignment::Top),
+                        title: "Align Top",
+                        svg {
+                            width: "16",
+                            height: "16",
+                            view_box: "0 0 24 24",
+                            fill: "none",
+                            stroke: "currentColor",
+                            stroke_width: "2",
+                            line { x1: "3", y1: "4", x2: "21", y2: "4" }
+                            rect { x: "6", y: "8", width: "4", height: "12" }
+                            rect { x: "14", y: "8", width: "4", height: "7" }
+                        }
+                    }
+                    button {
+                        class: "dioxus-flow-controls-button",
+                        onclick: move |_| align(NodeAlignment::Middle),
+                        title: "Align Middle",
+                        svg {
+                            width: "16",
+                            height: "16",
+                            view_box: "0 0 24 24",
+                            fill: "none",
+                            stroke: "currentColor",
+                            stroke_width: "2",
+                            line { x1: "3", y1: "12", x2: "21", y2: "12" }
+                            rect { x: "6", y: "6", width: "4", height: "12" }
+                            rect { x: "14", y: "8", width: "4", height: "8" }
+                        }
+                    }
+                    button {
+                        class: "dioxus-flow-controls-button",
+                        onclick: move |_| align(NodeAlignment::Bottom),
+                        title: "Align Bottom",
+                        svg {
+                            width: "16",
+                            height: "16",
+                            view_box: "0 0 24 24",
+                            fill: "none",
+                            stroke: "currentColor",
+                            stroke_width: "2",
+                            line { x1: "3", y1: "20", x2: "21", y2: "20" }
+                            rect { x: "6", y: "4", width: "4", height: "12" }
+                            rect { x: "14", y: "9", width: "4", height: "7" }
+                        }
+                    }
+                    button {
+                        class: "dioxus-flow-controls-button",
+                        onclick: move |_| distribute(DistributeDirection::Horizontal),
+                        title: "Distribute Horizontally",
+                        svg {
+                            width: "16",
+                            height: "16",
+                            view_box: "0 0 24 24",
+                            fill: "none",
+                            stroke: "currentColor",
+                            stroke_width: "2",
+                            rect { x: "3", y: "7", width: "4", height: "10" }
+                            rect { x: "10", y: "7", width: "4", height: "10" }
+                            rect { x: "17", y: "7", width: "4", height: "10" }
+                        }
+                    }
+                    button {
+                        class: "dioxus-flow-controls-button",
+                        onclick: move |_| distribute(DistributeDirection::Vertical),
+                        title: "Distribute Vertically",
+                        svg {
+                            width: "16",
+                            height: "16",
+                            view_box: "0 0 24 24",
+                            fill: "none",
+                            stroke: "currentColor",
+                            stroke_width: "2",
+                            rect { x: "7", y: "3", width: "10", height: "4" }
+                            rect { x: "7", y: "10", width: "10", height: "4" }
+                            rect { x: "7", y: "17", width: "10", height: "4" }
+                        }
+                    }
+                }
+            }
+
             if props.show_interactive {
                 button {
                     class: "dioxus-flow-controls-button",
@@ -218,6 +357,14 @@ pub const CONTROLS_STYLES: &str = r#"
     z-index: 5;
 }
 
+.dioxus-flow-controls-group {
+    display: flex;
+    flex-direction: column;
+    gap: 4px;
+    padding-top: 4px;
+    border-top: 1px solid #ddd;
+}
+
 .dioxus-flow-controls-button {
     width: 32px;
     height: 32px;
@@ -1,7 +1,8 @@
 //! Edge component for connections between nodes.
 
-use crate::types::{Edge, EdgeId, EdgeType, HandlePosition, Position};
-use crate::utils::get_edge_path;
+use crate::components::label_editor::LabelEditor;
+use crate::types::{Edge, EdgeAnimation, EdgeId, EdgeStatus, EdgeType, HandlePosition, Position};
+use crate::utils::{escape_svg_id, get_edge_path, get_path_length};
 use dioxus::prelude::*;
 
 /// Edge component props.
@@ -22,11 +23,39 @@ pub struct EdgeComponentProps {
     /// Callback when edge is selected.
     #[props(default)]
     pub on_select: Option<EventHandler<EdgeId>>,
+    /// Callback when edge is double-clicked. Receives the screen position.
+    #[props(default)]
+    pub on_double_click: Option<EventHandler<(EdgeId, Position)>>,
+    /// Callback when the mouse enters the edge. Receives the screen position.
+    #[props(default)]
+    pub on_mouse_enter: Option<EventHandler<(EdgeId, Position)>>,
+    /// Callback when the mouse leaves the edge. Receives the screen position.
+    #[props(default)]
+    pub on_mouse_leave: Option<EventHandler<(EdgeId, Position)>>,
+    /// Callback when the edge is right-clicked. Receives the screen position.
+    #[props(default)]
+    pub on_context_menu: Option<EventHandler<(EdgeId, Position)>>,
+    /// Whether the label is being edited in place.
+    #[props(default)]
+    pub editing_label: bool,
+    /// Callback when the label is double-clicked to edit it.
+    #[props(default)]
+    pub on_label_edit: Option<EventHandler<EdgeId>>,
+    /// Callback with the edited label text.
+    #[props(default)]
+    pub on_label_commit: Option<EventHandler<String>>,
+    /// Callback when label editing is cancelled.
+    #[props(default)]
+    pub on_label_cancel: Option<EventHandler<()>>,
+    /// Whether to show the label. Hidden when zoomed out far enough.
+    #[props(default = true)]
+    pub show_label: bool,
 }
 
 /// Edge component for rendering connections.
 #[component]
 pub fn EdgeComponent(props: EdgeComponentProps) -> Element {
+    let mut hovered = use_signal(|| false);
     let edge = &props.edge;
     let path = get_edge_path(
         edge.edge_type,
@@ -41,11 +70,29 @@ pub fn EdgeComponent(props: EdgeComponentProps) -> Element {
     } else {
         ""
     };
-    let animated_class = if edge.animated {
-        "dioxus-flow-edge-animated"
+    let animated_class = match edge.animation {
+        EdgeAnimation::Dash => "dioxus-flow-edge-animated",
+        EdgeAnimation::Pulse => "dioxus-flow-edge-pulse",
+        _ if edge.animated => "dioxus-flow-edge-animated",
+        _ => "",
+    };
+    let hovered_class = if *hovered.read() {
+        "dioxus-flow-edge-hovered"
     } else {
         ""
     };
+    let status_class = match edge.status {
+        EdgeStatus::Idle => "",
+        EdgeStatus::Active => "dioxus-flow-edge-status-active",
+        EdgeStatus::Error => "dioxus-flow-edge-status-error",
+    };
+
+    // Gradient strokes reference a per-edge gradient definition
+    let gradient_id = format!("dioxus-flow-edge-gradient-{}", escape_svg_id(&edge.id));
+    let stroke = match edge.animation {
+        EdgeAnimation::Gradient { .. } => format!("url(#{})", gradient_id),
+        _ => edge.stroke.clone(),
+    };
 
     let edge_type_class = match edge.edge_type {
         EdgeType::Bezier => "dioxus-flow-edge-bezier",
@@ -54,7 +101,12 @@ pub fn EdgeComponent(props: EdgeComponentProps) -> Element {
         EdgeType::SmoothStep => "dioxus-flow-edge-smoothstep",
     };
 
-    let on_select = props.on_select.clone();
+    let on_select = props.on_select;
+    let on_double_click = props.on_double_click;
+    let on_mouse_enter = props.on_mouse_enter;
+    let on_mouse_leave = props.on_mouse_leave;
+    let on_context_menu = props.on_context_menu;
+    let on_label_edit = props.on_label_edit;
     let edge_id = edge.id.clone();
 
     // Calculate label position (middle of the path)
@@ -63,42 +115,152 @@ pub fn EdgeComponent(props: EdgeComponentProps) -> Element {
 
     rsx! {
         g {
-            class: "dioxus-flow-edge {edge_type_class} {selected_class} {animated_class} {edge.class}",
+            class: "dioxus-flow-edge {edge_type_class} {selected_class} {hovered_class} {animated_class} {status_class} {edge.class}",
             "data-id": "{edge.id}",
+            onmouseenter: {
+                let edge_id = edge_id.clone();
+                move |evt: MouseEvent| {
+                    hovered.set(true);
+                    if let Some(handler) = &on_mouse_enter {
+                        let coords = evt.client_coordinates();
+                        handler.call((edge_id.clone(), Position::new(coords.x, coords.y)));
+                    }
+                }
+            },
+            onmouseleave: {
+                let edge_id = edge_id.clone();
+                move |evt: MouseEvent| {
+                    hovered.set(false);
+                    if let Some(handler) = &on_mouse_leave {
+                        let coords = evt.client_coordinates();
+                        handler.call((edge_id.clone(), Position::new(coords.x, coords.y)));
+                    }
+                }
+            },
             // Invisible wider path for easier selection
             path {
                 class: "dioxus-flow-edge-interaction",
                 d: "{path}",
                 fill: "none",
                 stroke: "transparent",
-                stroke_width: "20",
-                onclick: move |evt| {
-                    evt.stop_propagation();
-                    if let Some(handler) = &on_select {
-                        handler.call(edge_id.clone());
+                stroke_width: "{edge.interaction_width}",
+                onclick: {
+                    let edge_id = edge_id.clone();
+                    move |evt: MouseEvent| {
+                        evt.stop_propagation();
+                        if let Some(handler) = &on_select {
+                            handler.call(edge_id.clone());
+                        }
+                    }
+                },
+                ondoubleclick: {
+                    let edge_id = edge_id.clone();
+                    move |evt: MouseEvent| {
+                        evt.stop_propagation();
+                        if let Some(handler) = &on_double_click {
+                            let coords = evt.client_coordinates();
+                            handler.call((edge_id.clone(), Position::new(coords.x, coords.y)));
+                        }
                     }
                 },
+                oncontextmenu: {
+                    let edge_id = edge_id.clone();
+                    move |evt: MouseEvent| {
+                        if let Some(handler) = &on_context_menu {
+                            evt.prevent_default();
+                            evt.stop_propagation();
+                            let coords = evt.client_coordinates();
+                            handler.call((edge_id.clone(), Position::new(coords.x, coords.y)));
+                        }
+                    }
+                },
+                // Don't start panning when pressing on an edge
+                onmousedown: move |evt: MouseEvent| {
+                    evt.stop_propagation();
+                },
+            }
+            if let EdgeAnimation::Gradient { source_color, target_color } = &edge.animation {
+                defs {
+                    linearGradient {
+                        id: "{gradient_id}",
+                        gradient_units: "userSpaceOnUse",
+                        x1: "{props.source_position.x}",
+                        y1: "{props.source_position.y}",
+                        x2: "{props.target_position.x}",
+                        y2: "{props.target_position.y}",
+                        stop { offset: "0%", stop_color: "{source_color}" }
+                        stop { offset: "100%", stop_color: "{target_color}" }
+                    }
+                }
             }
             // Visible edge path
             path {
                 class: "dioxus-flow-edge-path",
                 d: "{path}",
                 fill: "none",
-                stroke: "{edge.stroke}",
+                stroke: "{stroke}",
                 stroke_width: "{edge.stroke_width}",
                 marker_end: "url(#dioxus-flow-arrowhead)",
             }
+            // Data-flow particles
+            if let EdgeAnimation::Particles(options) = &edge.animation {
+                {
+                    // Particles travel at a constant speed, so the traversal time scales
+                    // with the length of the routed path
+                    let path_length = get_path_length(&path);
+                    let duration = (path_length / options.speed.max(1.0)).max(0.1);
+                    // A set color is inlined, since the status styles override the fill
+                    // attribute
+                    let color_style =
+                        options.color.as_ref().map(|c| format!("fill: {c};")).unwrap_or_default();
+                    let count = options.count.max(1);
+                    rsx! {
+                        for i in 0..count {
+                            circle {
+                                key: "{i}",
+                                class: "dioxus-flow-edge-particle",
+                                r: "{options.radius}",
+                                fill: "{edge.stroke}",
+                                style: "{color_style}",
+                                animateMotion {
+                                    dur: "{duration}s",
+                                    begin: "{-(duration * i as f64 / count as f64)}s",
+                                    repeat_count: "indefinite",
+                                    path: "{path}",
+                                }
+                            }
+                        }
+                    }
+                }
+            }
             // Edge label
-            if let Some(label) = &edge.label {
+            if let Some(label) = edge.label.as_ref().filter(|_| props.show_label) {
                 foreignObject {
                     x: "{label_x - 50.0}",
                     y: "{label_y - 10.0}",
                     width: "100",
                     height: "20",
                     class: "dioxus-flow-edge-label-container",
-                    div {
-                        class: "dioxus-flow-edge-label",
-                        "{label}"
+                    if props.editing_label {
+                        LabelEditor {
+                            value: label.clone(),
+                            on_commit: props.on_label_commit,
+                            on_cancel: props.on_label_cancel,
+                        }
+                    } else {
+                        div {
+                            class: "dioxus-flow-edge-label",
+                            ondoubleclick: {
+                                let edge_id = edge_id.clone();
+                                move |evt: MouseEvent| {
+                                    if let Some(handler) = &on_label_edit {
+                                        evt.stop_propagation();
+                                        handler.call(edge_id.clone());
+                                    }
+                                }
+                            },
+                            "{label}"
+                        }
                     }
                 }
             }
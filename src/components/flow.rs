@@ -1,23 +1,57 @@
 //! Main Flow component.
 
 use crate::components::edge::{ConnectionLine, EdgeComponent};
+use crate::components::helper_lines::HelperLinesRenderer;
 use crate::components::node::NodeComponent;
-use crate::hooks::FlowState;
-use crate::types::{Edge, FlowEvent, HandlePosition, NodeId, Position, SelectionRect, Viewport};
+use crate::components::subflow_breadcrumb::SubflowBreadcrumb;
+use crate::hooks::{
+    animate_viewport, use_zoom_range_props, Changes, FlowSnapshot, FlowState, NodeDetail,
+    DEFAULT_MAX_ZOOM, DEFAULT_MIN_ZOOM,
+};
+use crate::types::{
+    ContainerRect, DetailLevel, DistributeDirection, Edge, EdgeId, FitViewOptions, FlowEvent,
+    HandlePosition, HelperLines, LabelChange, LabelTarget, LevelOfDetail, Node, NodeAlignment,
+    NodeDragEvent, NodeId, NodeReparent, Position, SelectionRect, Viewport,
+};
+use crate::utils::{get_auto_pan_velocity, is_editable_target, target_matches};
+use dioxus::core::{current_scope_id, Runtime};
 use dioxus::html::geometry::WheelDelta;
 use dioxus::prelude::*;
+use dioxus::web::WebEventExt;
+use gloo_timers::future::TimeoutFuture;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
 
 /// Flow component props.
 #[derive(Props, Clone, PartialEq)]
 pub struct FlowProps<T: Clone + PartialEq + 'static> {
     /// Signal containing the flow state.
     pub state: Signal<FlowState<T>>,
-    /// Minimum zoom level.
-    #[props(default = 0.1)]
+    /// Minimum zoom level. Sets the flow state's zoom range, unless both limits are left
+    /// at their defaults, which keeps a range set on the state itself.
+    #[props(default = DEFAULT_MIN_ZOOM)]
     pub min_zoom: f64,
-    /// Maximum zoom level.
-    #[props(default = 4.0)]
+    /// Maximum zoom level. Sets the flow state's zoom range, unless both limits are left
+    /// at their defaults, which keeps a range set on the state itself.
+    #[props(default = DEFAULT_MAX_ZOOM)]
     pub max_zoom: f64,
+    /// Whether to render only the nodes and edges near the visible area. Selected and
+    /// dragged elements stay rendered wherever they are.
+    #[props(default)]
+    pub only_render_visible_elements: bool,
+    /// Distance in screen pixels around the visible area within which elements are still
+    /// rendered with `only_render_visible_elements`.
+    #[props(default = 100.0)]
+    pub visible_elements_margin: f64,
+    /// Whether to fit the view to the nodes once they have been measured.
+    #[props(default)]
+    pub fit_view_on_init: bool,
+    /// Options for `fit_view_on_init`.
+    #[props(default)]
+    pub fit_view_options: FitViewOptions,
     /// Whether panning is enabled.
     #[props(default = true)]
     pub pan_on_drag: bool,
@@ -45,12 +79,25 @@ pub struct FlowProps<T: Clone + PartialEq + 'static> {
     /// Whether elements can be deleted with keyboard.
     #[props(default = true)]
     pub elements_deletable: bool,
+    /// Whether Alt + L/C/R/T/M/B aligns the selected nodes (left, centre, right, top,
+    /// middle, bottom) and Alt + H/V distributes them horizontally/vertically.
+    #[props(default)]
+    pub align_shortcuts: bool,
     /// Whether multi-select is enabled (shift+click, box select).
     #[props(default = true)]
     pub multi_select: bool,
     /// Whether box selection on drag is enabled.
     #[props(default = false)]
     pub selection_on_drag: bool,
+    /// Class marking elements inside nodes that must not start a node drag.
+    #[props(default = "nodrag".to_string())]
+    pub no_drag_class_name: String,
+    /// Class marking elements that must not start panning or box selection.
+    #[props(default = "nopan".to_string())]
+    pub no_pan_class_name: String,
+    /// Class marking elements that keep wheel events (e.g. scrollable content in nodes).
+    #[props(default = "nowheel".to_string())]
+    pub no_wheel_class_name: String,
     /// Callback for node click.
     #[props(default)]
     pub on_node_click: Option<EventHandler<NodeId>>,
@@ -66,18 +113,78 @@ pub struct FlowProps<T: Clone + PartialEq + 'static> {
     /// Callback for edge click.
     #[props(default)]
     pub on_edge_click: Option<EventHandler<String>>,
+    /// Callback for edge double-click. Receives the flow position.
+    #[props(default)]
+    pub on_edge_double_click: Option<EventHandler<(EdgeId, Position)>>,
+    /// Callback for edge mouse enter. Receives the flow position.
+    #[props(default)]
+    pub on_edge_mouse_enter: Option<EventHandler<(EdgeId, Position)>>,
+    /// Callback for edge mouse leave. Receives the flow position.
+    #[props(default)]
+    pub on_edge_mouse_leave: Option<EventHandler<(EdgeId, Position)>>,
+    /// Callback for edge context menu (right-click). Receives the flow position.
+    #[props(default)]
+    pub on_edge_context_menu: Option<EventHandler<(EdgeId, Position)>>,
+    /// Whether node and edge labels can be renamed by double-clicking them.
+    #[props(default = true)]
+    pub labels_editable: bool,
+    /// Callback when a label was edited in place.
+    #[props(default)]
+    pub on_label_change: Option<EventHandler<LabelChange>>,
     /// Callback for pane click.
     #[props(default)]
     pub on_pane_click: Option<EventHandler<Position>>,
-    /// Callback for viewport change.
+    /// Callback whenever the viewport changes, whatever changed it.
     #[props(default)]
     pub on_viewport_change: Option<EventHandler<Viewport>>,
     /// Callback when a new connection is made.
     #[props(default)]
     pub on_connect: Option<EventHandler<Edge>>,
+    /// Distance in screen pixels the pointer has to move before a node drag starts.
+    #[props(default = 1.0)]
+    pub node_drag_threshold: f64,
+    /// Whether dragged nodes snap to and show alignment guides with other nodes.
+    #[props(default)]
+    pub helper_lines: bool,
+    /// Distance in screen pixels within which a dragged node snaps to a guide.
+    #[props(default = 5.0)]
+    pub helper_lines_threshold: f64,
+    /// Whether the viewport pans when dragging, connecting or box-selecting near its edge.
+    #[props(default = true)]
+    pub auto_pan: bool,
+    /// Width in pixels of the zone along the canvas edge that triggers auto-pan.
+    #[props(default = 40.0)]
+    pub auto_pan_edge_zone: f64,
+    /// Maximum auto-pan speed in pixels per frame.
+    #[props(default = 15.0)]
+    pub auto_pan_speed: f64,
+    /// Callback when a node drag starts.
+    #[props(default)]
+    pub on_node_drag_start: Option<EventHandler<NodeDragEvent>>,
     /// Callback when node position changes.
     #[props(default)]
     pub on_node_drag: Option<EventHandler<(NodeId, Position)>>,
+    /// Callback when a node drag stops.
+    #[props(default)]
+    pub on_node_drag_stop: Option<EventHandler<NodeDragEvent>>,
+    /// Whether groups show a collapse toggle.
+    #[props(default = true)]
+    pub collapsible_groups: bool,
+    /// Whether groups show a button that opens them as their own canvas.
+    #[props(default = true)]
+    pub group_drill_down: bool,
+    /// Whether to show the breadcrumb trail while a group is opened.
+    #[props(default = true)]
+    pub show_breadcrumb: bool,
+    /// Callback when a node is collapsed or expanded.
+    #[props(default)]
+    pub on_node_collapse: Option<EventHandler<(NodeId, bool)>>,
+    /// Callback when the opened group changes (`None` = the top-level flow).
+    #[props(default)]
+    pub on_subflow_change: Option<EventHandler<Option<NodeId>>>,
+    /// Callback when a node is dropped into or out of a group.
+    #[props(default)]
+    pub on_node_reparent: Option<EventHandler<NodeReparent>>,
     /// Callback when nodes are deleted.
     #[props(default)]
     pub on_nodes_delete: Option<EventHandler<Vec<NodeId>>>,
@@ -90,9 +197,20 @@ pub struct FlowProps<T: Clone + PartialEq + 'static> {
     /// Additional CSS class for the container.
     #[props(default)]
     pub class: String,
+    /// Zoom levels below which handles and edge labels are hidden and nodes are drawn as
+    /// placeholders. Everything is drawn at full detail when not set.
+    #[props(default)]
+    pub level_of_detail: Option<LevelOfDetail>,
     /// Custom node content renderer. Receives the node and should return the inner content.
+    /// Not called for placeholders; components in the content can call `use_node_detail`
+    /// to draw a compact variant when zoomed out.
     #[props(default)]
-    pub node_render: Option<Callback<crate::types::Node<T>, Element>>,
+    pub node_render: Option<Callback<Node<T>, Element>>,
+    /// Custom node content renderer that also receives the detail level the node is drawn
+    /// at, to draw a compact variant when zoomed out. Used instead of `node_render` when
+    /// set; not called for placeholders either.
+    #[props(default)]
+    pub node_render_with_detail: Option<Callback<(Node<T>, DetailLevel), Element>>,
     /// Additional children to render inside the flow.
     #[props(default)]
     pub children: Element,
@@ -102,19 +220,101 @@ pub struct FlowProps<T: Clone + PartialEq + 'static> {
 #[component]
 pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> Element {
     let mut state = props.state;
-    let mut dragging_node: Signal<Option<(NodeId, Position)>> = use_signal(|| None);
+    let mut dragging_node: Signal<Option<NodeDrag<T>>> = use_signal(|| None);
     let mut is_panning: Signal<bool> = use_signal(|| false);
     let mut last_mouse_pos: Signal<Option<Position>> = use_signal(|| None);
     let mut selection_box: Signal<Option<(Position, Position)>> = use_signal(|| None);
     let mut shift_held: Signal<bool> = use_signal(|| false);
     let mut last_click_time: Signal<f64> = use_signal(|| 0.0);
     let mut last_click_node: Signal<Option<NodeId>> = use_signal(|| None);
+    // Group the dragged node would be dropped into
+    let mut drop_target: Signal<Option<NodeId>> = use_signal(|| None);
+    // Alignment guides for the dragged node
+    let mut helper_lines: Signal<HelperLines> = use_signal(HelperLines::default);
+    // Last pointer position and canvas element, for auto-pan
+    let mut pointer_pos: Signal<Option<Position>> = use_signal(|| None);
+    let mut container_el: Signal<Option<web_sys::Element>> = use_signal(|| None);
+    let mut auto_pan_running: Signal<bool> = use_signal(|| false);
 
     // Touch state
     let mut touch_start: Signal<Option<(f64, f64)>> = use_signal(|| None);
     let mut pinch_distance: Signal<Option<f64>> = use_signal(|| None);
 
-    let viewport = state.read().viewport;
+    // Rendered node and container sizes are reported back into the state. Nodes register
+    // themselves with the measurer from context as they mount and unmount.
+    let measurer = use_context_provider(|| Rc::new(NodeMeasurer::new(state)));
+
+    // Nodes and edges register a revision counter from context as they mount. The
+    // counters of the elements the state records as changed are bumped after each write,
+    // so that only those elements recompute their view.
+    let revisions = use_context_provider(|| Rc::new(Revisions::default()));
+    let mut order = use_signal(|| 0u64);
+    use_effect(move || {
+        let changes = state.read().take_changes();
+        if changes.order {
+            order += 1;
+        }
+        revisions.bump(&changes);
+    });
+
+    use_zoom_range_props(state, props.min_zoom, props.max_zoom);
+
+    // Report every viewport change, whether from gestures, controls, the minimap,
+    // transitions or code
+    let on_viewport_change = props.on_viewport_change;
+    let mut last_viewport = use_signal(|| state.peek().viewport);
+    use_effect(move || {
+        let viewport = state.read().viewport;
+        if *last_viewport.peek() == viewport {
+            return;
+        }
+        last_viewport.set(viewport);
+        if let Some(handler) = &on_viewport_change {
+            handler.call(viewport);
+        }
+        state.write().emit_event(FlowEvent::ViewportChange(viewport));
+    });
+
+    // Fit the view once the container and every shown node have been measured
+    let mut initial_fit_done = use_signal(|| false);
+    let fit_view_on_init = props.fit_view_on_init;
+    let fit_view_options = props.fit_view_options.clone();
+    use_effect(move || {
+        if !fit_view_on_init || *initial_fit_done.peek() || container_el.read().is_none() {
+            return;
+        }
+        let target = {
+            let state_ref = state.read();
+            let measured = state_ref
+                .nodes
+                .iter()
+                .filter(|n| state_ref.is_node_visible(&n.id))
+                .all(|n| n.measured.is_some() || n.collapsed);
+            if !measured {
+                return;
+            }
+            state_ref.fit_view_viewport(&fit_view_options)
+        };
+        initial_fit_done.set(true);
+        if let Some(target) = target {
+            animate_viewport(state, target, fit_view_options.duration);
+        }
+    });
+
+    // The observer only reports resizes, so gestures re-read the container position in
+    // case the page scrolled
+    let mut sync_container = move || {
+        let Some(rect) = container_el.peek().as_ref().map(container_rect) else {
+            return;
+        };
+        if state.peek().container != rect {
+            state.write().set_container(rect);
+        }
+    };
+
+    // The flow only re-renders when the viewport or the set of rendered elements
+    // changes; each node and edge follows its own part of the state
+    let viewport = *use_memo(move || state.read().viewport).read();
     let transform = format!(
         "translate({}px, {}px) scale({})",
         viewport.x, viewport.y, viewport.zoom
@@ -122,29 +322,62 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
 
     // Handle keyboard events
     let elements_deletable = props.elements_deletable;
-    let on_nodes_delete = props.on_nodes_delete.clone();
-    let on_edges_delete = props.on_edges_delete.clone();
-    let on_selection_change = props.on_selection_change.clone();
+    let on_nodes_delete = props.on_nodes_delete;
+    let on_edges_delete = props.on_edges_delete;
+    let on_selection_change = props.on_selection_change;
+    let align_shortcuts = props.align_shortcuts;
 
     let on_key_down = move |evt: KeyboardEvent| {
+        // Typing into form controls or a label editor must not trigger shortcuts
+        if is_editable_target(evt.as_web_event().target()) || state.read().editing_label.is_some()
+        {
+            return;
+        }
+
+        // Alignment shortcuts go by physical key, since Alt changes the typed character
+        if align_shortcuts && evt.modifiers().alt() {
+            let code = format!("{:?}", evt.code());
+            let alignment = match code.as_str() {
+                "KeyL" => Some(NodeAlignment::Left),
+                "KeyC" => Some(NodeAlignment::Center),
+                "KeyR" => Some(NodeAlignment::Right),
+                "KeyT" => Some(NodeAlignment::Top),
+                "KeyM" => Some(NodeAlignment::Middle),
+                "KeyB" => Some(NodeAlignment::Bottom),
+                _ => None,
+            };
+            let direction = match code.as_str() {
+                "KeyH" => Some(DistributeDirection::Horizontal),
+                "KeyV" => Some(DistributeDirection::Vertical),
+                _ => None,
+            };
+            if let Some(alignment) = alignment {
+                evt.prevent_default();
+                state.write().align_selected_nodes(alignment);
+                return;
+            }
+            if let Some(direction) = direction {
+                evt.prevent_default();
+                state.write().distribute_selected_nodes(direction);
+                return;
+            }
+        }
         let key = evt.key();
         let ctrl_or_meta = evt.modifiers().meta() || evt.modifiers().ctrl();
         let key_str = format!("{:?}", key);
 
         match key_str.as_str() {
-            "Backspace" | "Delete" => {
-                if elements_deletable {
-                    state.write().save_to_history();
-                    let (deleted_nodes, deleted_edges) = state.write().delete_selected();
-                    if !deleted_nodes.is_empty() {
-                        if let Some(handler) = &on_nodes_delete {
-                            handler.call(deleted_nodes);
-                        }
+            "Backspace" | "Delete" if elements_deletable => {
+                state.write().save_to_history();
+                let (deleted_nodes, deleted_edges) = state.write().delete_selected();
+                if !deleted_nodes.is_empty() {
+                    if let Some(handler) = &on_nodes_delete {
+                        handler.call(deleted_nodes);
                     }
-                    if !deleted_edges.is_empty() {
-                        if let Some(handler) = &on_edges_delete {
-                            handler.call(deleted_edges);
-                        }
+                }
+                if !deleted_edges.is_empty() {
+                    if let Some(handler) = &on_edges_delete {
+                        handler.call(deleted_edges);
                     }
                 }
             }
@@ -232,47 +465,151 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
         }
     };
 
-    // Handle mouse move for dragging and panning
-    let on_mouse_move = {
-        let on_node_drag = props.on_node_drag.clone();
-        move |evt: MouseEvent| {
-            let coords = evt.client_coordinates();
-            let current_pos = Position::new(coords.x, coords.y);
+    // Move whatever the pointer is driving (dragged nodes, box selection or connection
+    // line) to a screen position. Auto-pan re-runs this as the viewport moves underneath.
+    let move_pointer = {
+        let on_node_drag = props.on_node_drag;
+        let helper_lines_enabled = props.helper_lines;
+        let helper_lines_threshold = props.helper_lines_threshold;
+        move |current_pos: Position| {
+            let zoom = state.read().viewport.zoom;
+            let flow_pos = state.read().screen_to_flow(current_pos.x, current_pos.y);
 
             // Handle node dragging
             let dragging_info = dragging_node.read().clone();
-            if let Some((node_id, start_pos)) = dragging_info {
-                let zoom = state.read().viewport.zoom;
-                let dx = (current_pos.x - start_pos.x) / zoom;
-                let dy = (current_pos.y - start_pos.y) / zoom;
-
-                let node_pos = state.read().get_node(&node_id).map(|n| n.position);
-                if let Some(pos) = node_pos {
-                    let new_pos = Position::new(pos.x + dx, pos.y + dy);
-                    state.write().update_node_position(&node_id, new_pos);
-
-                    if let Some(handler) = &on_node_drag {
-                        handler.call((node_id.clone(), new_pos));
+            if let Some(drag) = dragging_info.filter(|d| d.snapshot.is_some()) {
+                // Offset from the drag start so snapping doesn't swallow small moves
+                let dx = flow_pos.x - drag.flow_start.x;
+                let dy = flow_pos.y - drag.flow_start.y;
+
+                let mut moved = state.write().drag_nodes(&drag.starts, dx, dy);
+
+                // Snap the pressed node onto guides from the other nodes
+                if helper_lines_enabled
+                    && drag.starts.iter().any(|(id, _)| id == &drag.node_id)
+                {
+                    let dragged: Vec<NodeId> =
+                        drag.starts.iter().map(|(id, _)| id.clone()).collect();
+                    let (lines, shift) = state.read().helper_lines(
+                        &drag.node_id,
+                        &dragged,
+                        helper_lines_threshold / zoom,
+                    );
+                    if shift.x != 0.0 || shift.y != 0.0 {
+                        moved = state.write().drag_nodes(&drag.starts, dx + shift.x, dy + shift.y);
+                    }
+                    if *helper_lines.peek() != lines {
+                        helper_lines.set(lines);
                     }
-                    // Emit event for centralized handling
-                    state.write().emit_event(FlowEvent::NodeDrag {
-                        id: node_id.clone(),
-                        position: new_pos,
-                    });
                 }
-
-                dragging_node.set(Some((node_id, current_pos)));
+                if let Some(handler) = &on_node_drag {
+                    for (node_id, new_pos) in &moved {
+                        handler.call((node_id.clone(), *new_pos));
+                    }
+                }
+                // Emit event for centralized handling
+                state.write().emit_event(FlowEvent::NodeDrag(NodeDragEvent {
+                    id: drag.node_id.clone(),
+                    nodes: moved,
+                }));
+
+                let target = reparent_target(&state.read(), &drag.node_id);
+                if *drop_target.peek() != target {
+                    drop_target.set(target);
+                }
                 return;
             }
 
             // Handle box selection
             let selection_box_val = *selection_box.read();
             if let Some((start, _)) = selection_box_val {
-                let vp = state.read().viewport;
-                let end_flow = vp.screen_to_flow(current_pos.x, current_pos.y);
-                selection_box.set(Some((start, end_flow)));
+                selection_box.set(Some((start, flow_pos)));
+                return;
+            }
+
+            // Update connection line if connecting
+            if state.read().connection.is_some() {
+                state.write().update_connection(flow_pos);
+            }
+        }
+    };
+
+    // Auto-pan velocity while dragging, connecting or box-selecting near the canvas edge
+    let auto_pan = props.auto_pan;
+    let auto_pan_edge_zone = props.auto_pan_edge_zone;
+    let auto_pan_speed = props.auto_pan_speed;
+    let auto_pan_velocity = move || -> Option<(f64, f64)> {
+        let pointer = (*pointer_pos.read())?;
+        let active = dragging_node.read().as_ref().is_some_and(|d| d.snapshot.is_some())
+            || selection_box.read().is_some()
+            || state.read().connection.is_some();
+        if !active {
+            return None;
+        }
+        let rect = state.read().container;
+        let velocity = get_auto_pan_velocity(
+            pointer,
+            (rect.x, rect.y, rect.width, rect.height),
+            auto_pan_edge_zone,
+            auto_pan_speed,
+        );
+        (velocity != (0.0, 0.0)).then_some(velocity)
+    };
+
+    // Runs on an animation timer until the pointer leaves the edge zone or the
+    // interaction ends
+    let mut start_auto_pan = {
+        let mut move_pointer = move_pointer;
+        move || {
+            if !auto_pan || *auto_pan_running.peek() || auto_pan_velocity().is_none() {
                 return;
             }
+            auto_pan_running.set(true);
+            spawn(async move {
+                loop {
+                    TimeoutFuture::new(AUTO_PAN_INTERVAL_MS).await;
+                    let Some((vx, vy)) = auto_pan_velocity() else {
+                        break;
+                    };
+                    state.write().pan(vx, vy);
+                    if let Some(pointer) = *pointer_pos.read() {
+                        move_pointer(pointer);
+                    }
+                }
+                auto_pan_running.set(false);
+            });
+        }
+    };
+
+    // Handle mouse move for dragging and panning
+    let on_mouse_move = {
+        let on_node_drag_start = props.on_node_drag_start;
+        let node_drag_threshold = props.node_drag_threshold;
+        let mut move_pointer = move_pointer;
+        move |evt: MouseEvent| {
+            let coords = evt.client_coordinates();
+            let current_pos = Position::new(coords.x, coords.y);
+            pointer_pos.set(Some(current_pos));
+
+            // Start a node drag once the pointer passes the threshold
+            let dragging_info = dragging_node.read().clone();
+            if let Some(mut drag) = dragging_info.filter(|d| d.snapshot.is_none()) {
+                // Plain clicks shouldn't move nodes, so wait for the threshold
+                let distance = crate::utils::distance(drag.pointer_start, current_pos);
+                if distance < node_drag_threshold {
+                    return;
+                }
+                drag.snapshot = Some(state.read().snapshot());
+                let event = NodeDragEvent {
+                    id: drag.node_id.clone(),
+                    nodes: drag.starts.clone(),
+                };
+                if let Some(handler) = &on_node_drag_start {
+                    handler.call(event.clone());
+                }
+                state.write().emit_event(FlowEvent::NodeDragStart(event));
+                dragging_node.set(Some(drag));
+            }
 
             // Handle panning
             if *is_panning.read() {
@@ -285,19 +622,17 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
                 return;
             }
 
-            // Update connection line if connecting
-            if state.read().connection.is_some() {
-                let vp = state.read().viewport;
-                let flow_pos = vp.screen_to_flow(current_pos.x, current_pos.y);
-                state.write().update_connection(flow_pos);
-            }
+            move_pointer(current_pos);
+            start_auto_pan();
         }
     };
 
     // Handle mouse up - end dragging/panning/selection
     let multi_select = props.multi_select;
     let on_mouse_up = {
-        let on_selection_change = props.on_selection_change.clone();
+        let on_selection_change = props.on_selection_change;
+        let on_node_reparent = props.on_node_reparent;
+        let on_node_drag_stop = props.on_node_drag_stop;
         move |_evt: MouseEvent| {
             // Complete box selection
             let selection_box_val = *selection_box.read();
@@ -328,8 +663,81 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
                 selection_box.set(None);
             }
 
-            if dragging_node.read().is_some() {
-                dragging_node.set(None);
+            // Only drags past the threshold have a snapshot
+            let dragged = dragging_node.take();
+            if let Some(NodeDrag {
+                node_id: dragged_id,
+                starts,
+                snapshot: Some(snapshot),
+                ..
+            }) = dragged
+            {
+                // End positions are taken before reparenting, so that they are in the same
+                // frame as the start positions and the drag events
+                let final_positions: Vec<(NodeId, Position)> = {
+                    let state_ref = state.read();
+                    starts
+                        .iter()
+                        .filter_map(|(id, _)| state_ref.get_node(id).map(|n| (id.clone(), n.position)))
+                        .collect()
+                };
+                let moved = final_positions
+                    .iter()
+                    .zip(starts.iter())
+                    .any(|((_, end), (_, start))| end != start);
+
+                // Drop the nodes into the group under them, or out of their current one
+                let mut reparented = false;
+                for (node_id, _) in starts.iter().cloned() {
+                    let (eligible, old_parent) = {
+                        let state_ref = state.read();
+                        let node = state_ref.get_node(&node_id);
+                        let old_parent = node.and_then(|n| n.parent_id.clone());
+                        let eligible = node.is_some_and(|n| !n.extent_parent)
+                            && old_parent
+                                .as_ref()
+                                .is_none_or(|p| state_ref.get_node(p).is_some_and(|p| p.is_group()));
+                        (eligible, old_parent)
+                    };
+                    let new_parent = state.read().find_drop_target(&node_id);
+                    if eligible
+                        && new_parent != old_parent
+                        && state.write().reparent_node(&node_id, new_parent.clone())
+                    {
+                        reparented = true;
+                        if let Some(handler) = &on_node_reparent {
+                            handler.call(NodeReparent {
+                                id: node_id.clone(),
+                                old_parent: old_parent.clone(),
+                                new_parent: new_parent.clone(),
+                            });
+                        }
+                        state.write().emit_event(FlowEvent::NodeReparent {
+                            id: node_id,
+                            old_parent,
+                            new_parent,
+                        });
+                    }
+                }
+
+                // One undo entry for the whole gesture, and only if something changed
+                if moved || reparented {
+                    state.write().push_history(snapshot);
+                }
+                let event = NodeDragEvent {
+                    id: dragged_id,
+                    nodes: final_positions,
+                };
+                if let Some(handler) = &on_node_drag_stop {
+                    handler.call(event.clone());
+                }
+                state.write().emit_event(FlowEvent::NodeDragEnd(event));
+            }
+            if drop_target.peek().is_some() {
+                drop_target.set(None);
+            }
+            if !helper_lines.peek().is_empty() {
+                helper_lines.set(HelperLines::default());
             }
             if *is_panning.read() {
                 is_panning.set(false);
@@ -345,13 +753,18 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
     // Handle mouse down on pane - start panning or box selection
     let pan_on_drag = props.pan_on_drag;
     let selection_on_drag = props.selection_on_drag;
-    let on_pane_click = props.on_pane_click.clone();
+    let on_pane_click = props.on_pane_click;
     let zoom_on_double_click = props.zoom_on_double_click;
-    let min_zoom = props.min_zoom;
-    let max_zoom = props.max_zoom;
+    let no_pan_selector = format!(".{}", props.no_pan_class_name);
     let on_mouse_down = move |evt: MouseEvent| {
+        let web_evt = evt.as_web_event();
+        if target_matches(web_evt.target(), web_evt.current_target(), &no_pan_selector) {
+            return;
+        }
+        sync_container();
         let coords = evt.client_coordinates();
         let current_pos = Position::new(coords.x, coords.y);
+        let flow_pos = state.read().screen_to_flow(coords.x, coords.y);
 
         // Check for double-click (zoom)
         let now = web_sys::window()
@@ -360,9 +773,9 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
             .unwrap_or(0.0);
         if zoom_on_double_click && now - *last_click_time.read() < 300.0 {
             // Double-click: zoom in
-            let vp = state.read().viewport;
-            let new_zoom = (vp.zoom * 1.5).min(max_zoom);
-            state.write().set_zoom(new_zoom, coords.x, coords.y);
+            let new_zoom = state.read().viewport.zoom * 1.5;
+            let center = state.read().container.to_local(coords.x, coords.y);
+            state.write().set_zoom(new_zoom, center.x, center.y);
             last_click_time.set(0.0);
             return;
         }
@@ -371,8 +784,6 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
 
         // Start box selection if shift is held or selection_on_drag is enabled
         if selection_on_drag || (*shift_held.read() && !pan_on_drag) {
-            let vp = state.read().viewport;
-            let flow_pos = vp.screen_to_flow(coords.x, coords.y);
             selection_box.set(Some((flow_pos, flow_pos)));
             return;
         }
@@ -389,8 +800,6 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
         }
 
         if let Some(handler) = &on_pane_click {
-            let vp = state.read().viewport;
-            let flow_pos = vp.screen_to_flow(coords.x, coords.y);
             handler.call(flow_pos);
         }
     };
@@ -398,9 +807,15 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
     // Handle wheel for zooming or panning
     let zoom_on_scroll = props.zoom_on_scroll;
     let pan_on_scroll = props.pan_on_scroll;
-    let on_viewport_change = props.on_viewport_change.clone();
+    let no_wheel_selector = format!(".{}", props.no_wheel_class_name);
     let on_wheel = move |evt: WheelEvent| {
+        // Let `nowheel` elements scroll natively
+        let web_evt = evt.as_web_event();
+        if target_matches(web_evt.target(), web_evt.current_target(), &no_wheel_selector) {
+            return;
+        }
         evt.prevent_default();
+        sync_container();
         let wheel_delta = evt.delta();
         let delta_y = match wheel_delta {
             WheelDelta::Pixels(p) => p.y,
@@ -419,27 +834,18 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
         } else if zoom_on_scroll {
             // Zoom
             let delta = -delta_y / 500.0;
-            let coords = evt.client_coordinates();
+            let client = evt.client_coordinates();
 
             let mut state_mut = state.write();
-            let old_zoom = state_mut.viewport.zoom;
-            let new_zoom = (old_zoom + delta).clamp(min_zoom, max_zoom);
-
+            let coords = state_mut.container.to_local(client.x, client.y);
             // Zoom around mouse position
-            state_mut.viewport.x =
-                coords.x - (coords.x - state_mut.viewport.x) * new_zoom / old_zoom;
-            state_mut.viewport.y =
-                coords.y - (coords.y - state_mut.viewport.y) * new_zoom / old_zoom;
-            state_mut.viewport.zoom = new_zoom;
-
-            if let Some(handler) = &on_viewport_change {
-                handler.call(state_mut.viewport);
-            }
+            state_mut.zoom(delta, coords.x, coords.y);
         }
     };
 
     // Touch event handlers
     let on_touch_start = move |evt: TouchEvent| {
+        sync_container();
         let touches = evt.touches();
         if touches.len() == 1 {
             // Single touch - start panning
@@ -493,14 +899,10 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
                         (t1.client_coordinates().y + t2.client_coordinates().y) / 2.0;
 
                     let mut state_mut = state.write();
-                    let old_zoom = state_mut.viewport.zoom;
-                    let new_zoom = (old_zoom * scale).clamp(min_zoom, max_zoom);
-
-                    state_mut.viewport.x =
-                        center_x - (center_x - state_mut.viewport.x) * new_zoom / old_zoom;
-                    state_mut.viewport.y =
-                        center_y - (center_y - state_mut.viewport.y) * new_zoom / old_zoom;
-                    state_mut.viewport.zoom = new_zoom;
+                    let Position { x: center_x, y: center_y } =
+                        state_mut.container.to_local(center_x, center_y);
+                    let new_zoom = state_mut.viewport.zoom * scale;
+                    state_mut.set_zoom(new_zoom, center_x, center_y);
                 }
                 pinch_distance.set(Some(distance));
             }
@@ -514,11 +916,11 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
     };
 
     // Node event handlers
-    let on_node_click = props.on_node_click.clone();
-    let on_node_double_click = props.on_node_double_click.clone();
+    let on_node_click = props.on_node_click;
+    let on_node_double_click = props.on_node_double_click;
     let nodes_selectable = props.nodes_selectable;
     let on_node_select = {
-        let on_selection_change = props.on_selection_change.clone();
+        let on_selection_change = props.on_selection_change;
         move |node_id: NodeId| {
             // Check for double-click
             let now = web_sys::window()
@@ -563,18 +965,25 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
     };
 
     let nodes_draggable = props.nodes_draggable;
-    let on_node_drag_start = move |(node_id, pos): (NodeId, Position)| {
+    let on_node_press = move |(node_id, pos): (NodeId, Position)| {
         if nodes_draggable {
             let is_draggable = state.read().get_node(&node_id).map(|n| n.draggable).unwrap_or(false);
             if is_draggable {
-                state.write().save_to_history();
-                dragging_node.set(Some((node_id, pos)));
+                let starts = state.read().drag_start_positions(&node_id);
+                let flow_start = state.read().screen_to_flow(pos.x, pos.y);
+                dragging_node.set(Some(NodeDrag {
+                    node_id,
+                    pointer_start: pos,
+                    flow_start,
+                    starts,
+                    snapshot: None,
+                }));
             }
         }
     };
 
     let nodes_connectable = props.nodes_connectable;
-    let on_connect = props.on_connect.clone();
+    let on_connect = props.on_connect;
     let on_connect_start = move |(node_id, handle_pos): (NodeId, HandlePosition)| {
         if !nodes_connectable {
             return;
@@ -615,10 +1024,10 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
     };
 
     // Edge event handlers
-    let on_edge_click = props.on_edge_click.clone();
+    let on_edge_click = props.on_edge_click;
     let edges_selectable = props.edges_selectable;
     let on_edge_select = {
-        let on_selection_change = props.on_selection_change.clone();
+        let on_selection_change = props.on_selection_change;
         move |edge_id: String| {
             if edges_selectable {
                 let multi = *shift_held.read() && multi_select;
@@ -638,22 +1047,204 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
         }
     };
 
-    // Read state for rendering - sort nodes by z-index
-    let nodes = state
-        .read()
-        .nodes_sorted_by_z_index()
-        .into_iter()
-        .cloned()
-        .collect::<Vec<_>>();
-    let edges = state.read().edges.clone();
-    let connection = state.read().connection.clone();
-    let current_zoom = state.read().viewport.zoom;
+    let on_edge_double_click_prop = props.on_edge_double_click;
+    let on_edge_double_click = move |(edge_id, screen_pos): (EdgeId, Position)| {
+        let flow_pos = state.read().screen_to_flow(screen_pos.x, screen_pos.y);
+        if let Some(handler) = &on_edge_double_click_prop {
+            handler.call((edge_id.clone(), flow_pos));
+        }
+        state.write().emit_event(FlowEvent::EdgeDoubleClick {
+            id: edge_id,
+            position: flow_pos,
+        });
+    };
+
+    // Inline label editing
+    let labels_editable = props.labels_editable;
+    let on_label_change = props.on_label_change;
+    let on_node_label_edit = move |node_id: NodeId| {
+        state.write().start_label_edit(LabelTarget::Node(node_id));
+    };
+    let on_edge_label_edit = move |edge_id: EdgeId| {
+        state.write().start_label_edit(LabelTarget::Edge(edge_id));
+    };
+    let on_label_commit = move |text: String| {
+        let change = state.write().commit_label_edit(&text);
+        if let Some(change) = change {
+            if let Some(handler) = &on_label_change {
+                handler.call(change.clone());
+            }
+            state.write().emit_event(FlowEvent::LabelChange(change));
+        }
+    };
+    let on_label_cancel = move |_: ()| {
+        state.write().cancel_label_edit();
+    };
+
+    let on_edge_mouse_enter_prop = props.on_edge_mouse_enter;
+    let on_edge_mouse_enter = move |(edge_id, screen_pos): (EdgeId, Position)| {
+        let flow_pos = state.read().screen_to_flow(screen_pos.x, screen_pos.y);
+        if let Some(handler) = &on_edge_mouse_enter_prop {
+            handler.call((edge_id.clone(), flow_pos));
+        }
+        // Queuing records no node or edge change, so hovering doesn't re-render them
+        state.write().emit_event(FlowEvent::EdgeMouseEnter {
+            id: edge_id,
+            position: flow_pos,
+        });
+    };
+
+    let on_edge_mouse_leave_prop = props.on_edge_mouse_leave;
+    let on_edge_mouse_leave = move |(edge_id, screen_pos): (EdgeId, Position)| {
+        let flow_pos = state.read().screen_to_flow(screen_pos.x, screen_pos.y);
+        if let Some(handler) = &on_edge_mouse_leave_prop {
+            handler.call((edge_id.clone(), flow_pos));
+        }
+        state.write().emit_event(FlowEvent::EdgeMouseLeave {
+            id: edge_id,
+            position: flow_pos,
+        });
+    };
+
+    let on_edge_context_menu_prop = props.on_edge_context_menu;
+    let on_edge_context_menu = move |(edge_id, screen_pos): (EdgeId, Position)| {
+        let flow_pos = state.read().screen_to_flow(screen_pos.x, screen_pos.y);
+        if let Some(handler) = &on_edge_context_menu_prop {
+            handler.call((edge_id.clone(), flow_pos));
+        }
+        state.write().emit_event(FlowEvent::EdgeContextMenu {
+            id: edge_id,
+            position: flow_pos,
+        });
+    };
+
+    // Group handlers
+    let on_node_collapse = props.on_node_collapse;
+    let on_toggle_collapse = move |node_id: NodeId| {
+        state.write().save_to_history();
+        let collapsed = state.write().toggle_node_collapsed(&node_id);
+        if let Some(collapsed) = collapsed {
+            if let Some(handler) = &on_node_collapse {
+                handler.call((node_id.clone(), collapsed));
+            }
+            state.write().emit_event(FlowEvent::NodeCollapse {
+                id: node_id,
+                collapsed,
+            });
+        }
+    };
+
+    let on_subflow_change = props.on_subflow_change;
+    let on_open_subflow = move |node_id: NodeId| {
+        if state.write().enter_subflow(&node_id) {
+            if let Some(handler) = &on_subflow_change {
+                handler.call(Some(node_id.clone()));
+            }
+            state.write().emit_event(FlowEvent::SubflowEnter(node_id));
+        }
+    };
+    let on_breadcrumb_navigate = move |_depth: usize| {
+        if let Some(handler) = &on_subflow_change {
+            handler.call(state.read().subflow_root().cloned());
+        }
+    };
+    let collapsible_groups = props.collapsible_groups;
+    let group_drill_down = props.group_drill_down;
+
+    // IDs of the rendered nodes, sorted by z-index and skipping hidden ones, and of the
+    // rendered edges
+    let only_visible = props.only_render_visible_elements;
+    let visible_margin = props.visible_elements_margin;
+    let rendered = use_memo(use_reactive!(|only_visible, visible_margin| {
+        // Without culling, only adding, removing or restacking elements changes the lists
+        let state_ref = if only_visible {
+            state.read()
+        } else {
+            order.read();
+            state.peek()
+        };
+        if only_visible {
+            let margin = visible_margin / state_ref.viewport.zoom;
+            // Nodes that must stay mounted while off-screen
+            let mut keep = state_ref.selected_nodes.clone();
+            if let Some(drag) = dragging_node.read().as_ref() {
+                keep.extend(drag.starts.iter().map(|(id, _)| id.clone()));
+            }
+            if let Some(conn) = &state_ref.connection {
+                keep.push(conn.source.clone());
+            }
+            if let Some(LabelTarget::Node(id)) = &state_ref.editing_label {
+                keep.push(id.clone());
+            }
+            let nodes = state_ref.nodes_near_visible_area(margin, &keep);
+            let edges = state_ref.edges_near_visible_area(margin, &keep);
+            (
+                nodes.into_iter().map(|n| n.id.clone()).collect::<Vec<_>>(),
+                edges.into_iter().map(|e| e.id.clone()).collect::<Vec<_>>(),
+            )
+        } else {
+            let nodes = state_ref
+                .nodes_sorted_by_z_index()
+                .into_iter()
+                .filter(|n| state_ref.is_node_visible(&n.id))
+                .map(|n| n.id.clone())
+                .collect::<Vec<_>>();
+            let edges = state_ref.edges.iter().map(|e| e.id.clone()).collect();
+            (nodes, edges)
+        }
+    }));
+    let (node_ids, edge_ids) = rendered.read().clone();
+
+    // Nodes being dragged, once the pointer passed the drag threshold
+    let dragged = use_memo(move || match dragging_node.read().as_ref() {
+        Some(drag) if drag.snapshot.is_some() => {
+            drag.starts.iter().map(|(id, _)| id.clone()).collect()
+        }
+        _ => Vec::new(),
+    });
+
+    // Zoom and detail level the nodes and edges are rendered at
+    let zoom = use_memo(move || state.read().viewport.zoom);
+    let level_of_detail = props.level_of_detail;
+    let detail = use_memo(use_reactive!(|level_of_detail| {
+        level_of_detail.map_or(DetailLevel::Full, |lod| lod.level(zoom()))
+    }));
+
+    // Connection line as (source, source side, pointer) in flow coordinates
+    let connection_line = use_memo(move || {
+        let state_ref = state.read();
+        let conn = state_ref.connection.as_ref()?;
+        let source_node = state_ref.get_node(&conn.source)?;
+        let source_pos = conn
+            .source_handle_id
+            .as_ref()
+            .and_then(|id| source_node.handle_position_by_id(id))
+            .unwrap_or_else(|| source_node.handle_position(conn.source_handle));
+        Some((source_pos, conn.source_handle, conn.target_position))
+    });
+    let connection_line = *connection_line.read();
+
+    // Stable handlers, so that re-rendering the flow leaves the nodes and edges alone
+    let on_node_select = use_callback(on_node_select);
+    let on_node_press = use_callback(on_node_press);
+    let on_connect_start = use_callback(on_connect_start);
+    let on_connect_end = use_callback(on_connect_end);
+    let on_toggle_collapse = use_callback(on_toggle_collapse);
+    let on_open_subflow = use_callback(on_open_subflow);
+    let on_node_label_edit = use_callback(on_node_label_edit);
+    let on_edge_label_edit = use_callback(on_edge_label_edit);
+    let on_label_commit = use_callback(on_label_commit);
+    let on_label_cancel = use_callback(on_label_cancel);
+    let on_edge_select = use_callback(on_edge_select);
+    let on_edge_double_click = use_callback(on_edge_double_click);
+    let on_edge_mouse_enter = use_callback(on_edge_mouse_enter);
+    let on_edge_mouse_leave = use_callback(on_edge_mouse_leave);
+    let on_edge_context_menu = use_callback(on_edge_context_menu);
 
     // Calculate selection box rect for rendering
     let selection_rect: Option<(f64, f64, f64, f64)> = (*selection_box.read()).map(|(start, end)| {
-        let vp = state.read().viewport;
-        let start_screen = vp.flow_to_screen(start.x, start.y);
-        let end_screen = vp.flow_to_screen(end.x, end.y);
+        let start_screen = viewport.flow_to_screen(start.x, start.y);
+        let end_screen = viewport.flow_to_screen(end.x, end.y);
         (
             start_screen.x.min(end_screen.x),
             start_screen.y.min(end_screen.y),
@@ -667,6 +1258,15 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
             class: "dioxus-flow-container {props.class}",
             style: "width: 100%; height: 100%; position: absolute; top: 0; left: 0; overflow: hidden; outline: none; z-index: 1;",
             tabindex: "0",
+            onmounted: {
+                let measurer = measurer.clone();
+                move |evt: MountedEvent| {
+                    let element: web_sys::Element = evt.as_web_event();
+                    measurer.observe(&element);
+                    container_el.set(Some(element));
+                    sync_container();
+                }
+            },
             onkeydown: on_key_down,
             onkeyup: on_key_up,
             onmousedown: on_mouse_down,
@@ -704,54 +1304,29 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
                 g {
                     style: "transform: {transform};",
                     // Render edges
-                    for edge in edges.iter() {
-                        {
-                            let source_node = state.read().get_node(&edge.source).cloned();
-                            let target_node = state.read().get_node(&edge.target).cloned();
-
-                            if let (Some(source), Some(target)) = (source_node, target_node) {
-                                // Try to get position and direction from handle ID first, fall back to handle position
-                                let (source_pos, source_dir) = edge.source_handle_id.as_ref()
-                                    .and_then(|id| source.handle_info_by_id(id))
-                                    .unwrap_or_else(|| (source.handle_position(edge.source_handle), edge.source_handle));
-                                let (target_pos, target_dir) = edge.target_handle_id.as_ref()
-                                    .and_then(|id| target.handle_info_by_id(id))
-                                    .unwrap_or_else(|| (target.handle_position(edge.target_handle), edge.target_handle));
-
-                                rsx! {
-                                    EdgeComponent {
-                                        key: "{edge.id}",
-                                        edge: edge.clone(),
-                                        source_position: source_pos,
-                                        target_position: target_pos,
-                                        source_handle_direction: source_dir,
-                                        target_handle_direction: target_dir,
-                                        on_select: on_edge_select,
-                                    }
-                                }
-                            } else {
-                                rsx! {}
-                            }
+                    for edge_id in edge_ids {
+                        FlowEdge {
+                            key: "{edge_id}",
+                            state: state,
+                            id: edge_id.clone(),
+                            detail: detail,
+                            on_select: on_edge_select,
+                            on_double_click: on_edge_double_click,
+                            on_mouse_enter: on_edge_mouse_enter,
+                            on_mouse_leave: on_edge_mouse_leave,
+                            on_context_menu: on_edge_context_menu,
+                            on_label_edit: labels_editable.then_some(on_edge_label_edit),
+                            on_label_commit: on_label_commit,
+                            on_label_cancel: on_label_cancel,
                         }
                     }
 
                     // Render connection line when connecting
-                    if let Some(conn) = connection {
-                        {
-                            if let Some(source_node) = state.read().get_node(&conn.source).cloned() {
-                                let source_pos = conn.source_handle_id.as_ref()
-                                    .and_then(|id| source_node.handle_position_by_id(id))
-                                    .unwrap_or_else(|| source_node.handle_position(conn.source_handle));
-                                rsx! {
-                                    ConnectionLine {
-                                        source: source_pos,
-                                        source_handle: conn.source_handle,
-                                        target: conn.target_position,
-                                    }
-                                }
-                            } else {
-                                rsx! {}
-                            }
+                    if let Some((source, source_handle, target)) = connection_line {
+                        ConnectionLine {
+                            source: source,
+                            source_handle: source_handle,
+                            target: target,
                         }
                     }
                 }
@@ -762,22 +1337,27 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
                 class: "dioxus-flow-nodes",
                 style: "position: absolute; top: 0; left: 0; width: 100%; height: 100%; transform: {transform}; transform-origin: 0 0; pointer-events: none;",
 
-                for node in nodes.iter() {
-                    {
-                        let custom_content = props.node_render.as_ref().map(|render| render.call(node.clone()));
-                        rsx! {
-                            NodeComponent {
-                                key: "{node.id}",
-                                node: node.clone(),
-                                zoom: current_zoom,
-                                dragging: dragging_node.read().as_ref().map(|(id, _)| id == &node.id).unwrap_or(false),
-                                on_select: on_node_select,
-                                on_drag_start: on_node_drag_start,
-                                on_connect_start: on_connect_start,
-                                on_connect_end: on_connect_end,
-                                {custom_content}
-                            }
-                        }
+                for node_id in node_ids {
+                    FlowNode {
+                        key: "{node_id}",
+                        state: state,
+                        id: node_id.clone(),
+                        zoom: zoom,
+                        detail: detail,
+                        dragged: dragged,
+                        drop_target: drop_target,
+                        no_drag_class: props.no_drag_class_name.clone(),
+                        node_render: props.node_render,
+                        node_render_with_detail: props.node_render_with_detail,
+                        on_toggle_collapse: collapsible_groups.then_some(on_toggle_collapse),
+                        on_open_subflow: group_drill_down.then_some(on_open_subflow),
+                        on_label_edit: labels_editable.then_some(on_node_label_edit),
+                        on_label_commit: on_label_commit,
+                        on_label_cancel: on_label_cancel,
+                        on_select: on_node_select,
+                        on_drag_start: on_node_press,
+                        on_connect_start: on_connect_start,
+                        on_connect_end: on_connect_end,
                     }
                 }
             }
@@ -790,12 +1370,415 @@ pub fn Flow<T: Clone + Default + PartialEq + 'static>(props: FlowProps<T>) -> El
                 }
             }
 
+            // Alignment guides while dragging
+            HelperLinesRenderer {
+                lines: helper_lines.read().clone(),
+                viewport: viewport,
+            }
+
+            // Breadcrumb trail while a group is opened
+            if props.show_breadcrumb {
+                SubflowBreadcrumb {
+                    state: state,
+                    on_navigate: on_breadcrumb_navigate,
+                }
+            }
+
             // Additional children
             {props.children}
         }
     }
 }
 
+/// Interval between auto-pan steps (about one frame).
+const AUTO_PAN_INTERVAL_MS: u32 = 16;
+
+/// Props of a node rendered by `Flow`.
+#[derive(Props, Clone, PartialEq)]
+struct FlowNodeProps<T: Clone + Default + PartialEq + 'static> {
+    state: Signal<FlowState<T>>,
+    id: NodeId,
+    zoom: Memo<f64>,
+    detail: Memo<DetailLevel>,
+    /// Nodes being dragged.
+    dragged: Memo<Vec<NodeId>>,
+    /// Group the dragged nodes would be dropped into.
+    drop_target: Signal<Option<NodeId>>,
+    no_drag_class: String,
+    node_render: Option<Callback<Node<T>, Element>>,
+    node_render_with_detail: Option<Callback<(Node<T>, DetailLevel), Element>>,
+    on_toggle_collapse: Option<EventHandler<NodeId>>,
+    on_open_subflow: Option<EventHandler<NodeId>>,
+    on_label_edit: Option<EventHandler<NodeId>>,
+    on_label_commit: EventHandler<String>,
+    on_label_cancel: EventHandler<()>,
+    on_select: EventHandler<NodeId>,
+    on_drag_start: EventHandler<(NodeId, Position)>,
+    on_connect_start: EventHandler<(NodeId, HandlePosition)>,
+    on_connect_end: EventHandler<(NodeId, HandlePosition)>,
+}
+
+/// The parts of the flow state a node is rendered from.
+#[derive(Clone, PartialEq)]
+struct NodeView<T: Clone + PartialEq + 'static> {
+    node: Node<T>,
+    detail: DetailLevel,
+    dragging: bool,
+    drop_target: bool,
+    proxy_handles: Vec<(HandlePosition, bool)>,
+    editing_label: bool,
+}
+
+/// A node of the flow. Its view is only recomputed when the state records a change to
+/// the node, the detail level changes or a drag starts or ends, not whenever another node
+/// moves or the zoom changes within a detail level.
+#[component]
+fn FlowNode<T: Clone + Default + PartialEq + 'static>(props: FlowNodeProps<T>) -> Element {
+    let state = props.state;
+    let zoom = props.zoom;
+    let detail = props.detail;
+    let dragged = props.dragged;
+    let drop_target = props.drop_target;
+    let id = props.id.clone();
+    let revision = use_revision(RevisionKind::Node, &id);
+
+    // Measure the element while it is mounted. Culled nodes unmount, so they have to stop
+    // being observed.
+    let measurer = use_context::<Rc<NodeMeasurer>>();
+    let on_mounted = use_callback({
+        let measurer = measurer.clone();
+        let id = id.clone();
+        move |evt: MountedEvent| measurer.observe_node(id.clone(), &evt.as_web_event())
+    });
+    use_drop({
+        let id = id.clone();
+        move || measurer.unobserve_node(&id)
+    });
+
+    let view = use_memo(use_reactive!(|id| {
+        revision.read();
+        let state_ref = state.peek();
+        let node = state_ref.get_node(&id)?.clone();
+        Some(NodeView {
+            node,
+            detail: detail(),
+            dragging: dragged.read().contains(&id),
+            drop_target: drop_target.read().as_ref() == Some(&id),
+            proxy_handles: state_ref.proxy_handles(&id),
+            editing_label: state_ref.editing_label == Some(LabelTarget::Node(id.clone())),
+        })
+    }));
+    // Custom content reads the detail level through `use_node_detail`
+    let detail = use_memo(move || view.read().as_ref().map_or(DetailLevel::Full, |v| v.detail));
+    use_context_provider(|| NodeDetail(detail));
+
+    let Some(view) = view.read().clone() else {
+        return rsx! {};
+    };
+    let custom_content = match (props.node_render_with_detail, props.node_render) {
+        _ if view.detail == DetailLevel::Placeholder => None,
+        (Some(render), _) => Some(render.call((view.node.clone(), view.detail))),
+        (None, Some(render)) => Some(render.call(view.node.clone())),
+        (None, None) => None,
+    };
+
+    rsx! {
+        NodeComponent {
+            node: view.node,
+            // Peeked, so that zooming within a detail level doesn't re-render every node
+            zoom: *zoom.peek(),
+            detail: view.detail,
+            dragging: view.dragging,
+            drop_target: view.drop_target,
+            no_drag_class: props.no_drag_class.clone(),
+            proxy_handles: view.proxy_handles,
+            on_toggle_collapse: props.on_toggle_collapse,
+            on_open_subflow: props.on_open_subflow,
+            editing_label: view.editing_label,
+            on_label_edit: props.on_label_edit,
+            on_label_commit: props.on_label_commit,
+            on_label_cancel: props.on_label_cancel,
+            on_select: props.on_select,
+            on_drag_start: props.on_drag_start,
+            on_connect_start: props.on_connect_start,
+            on_connect_end: props.on_connect_end,
+            on_mounted: on_mounted,
+            // Without a renderer the node shows its (editable) default label
+            children: custom_content.unwrap_or_else(VNode::empty),
+        }
+    }
+}
+
+/// Props of an edge rendered by `Flow`.
+#[derive(Props, Clone, PartialEq)]
+struct FlowEdgeProps<T: Clone + Default + PartialEq + 'static> {
+    state: Signal<FlowState<T>>,
+    id: EdgeId,
+    detail: Memo<DetailLevel>,
+    on_select: EventHandler<EdgeId>,
+    on_double_click: EventHandler<(EdgeId, Position)>,
+    on_mouse_enter: EventHandler<(EdgeId, Position)>,
+    on_mouse_leave: EventHandler<(EdgeId, Position)>,
+    on_context_menu: EventHandler<(EdgeId, Position)>,
+    on_label_edit: Option<EventHandler<EdgeId>>,
+    on_label_commit: EventHandler<String>,
+    on_label_cancel: EventHandler<()>,
+}
+
+/// The parts of the flow state an edge is rendered from.
+#[derive(Clone, PartialEq)]
+struct EdgeView {
+    edge: Edge,
+    endpoints: (Position, HandlePosition, Position, HandlePosition),
+    editing_label: bool,
+    show_label: bool,
+}
+
+/// An edge of the flow. Its view is only recomputed when the state records a change to
+/// the edge or its endpoints, or the detail level changes.
+#[component]
+fn FlowEdge<T: Clone + Default + PartialEq + 'static>(props: FlowEdgeProps<T>) -> Element {
+    let state = props.state;
+    let id = props.id.clone();
+    let detail = props.detail;
+    let revision = use_revision(RevisionKind::Edge, &id);
+    let view = use_memo(use_reactive!(|id| {
+        revision.read();
+        let state_ref = state.peek();
+        let edge = state_ref.get_edge(&id)?;
+        let detail = detail();
+        // Endpoints hidden in collapsed groups are re-routed to the group
+        let endpoints = state_ref.edge_endpoints(edge)?;
+        let editing_label = state_ref.editing_label == Some(LabelTarget::Edge(id.clone()));
+        Some(EdgeView {
+            edge: edge.clone(),
+            endpoints,
+            editing_label,
+            // Keep a label that is being renamed
+            show_label: detail == DetailLevel::Full || editing_label,
+        })
+    }));
+    let Some(view) = view.read().clone() else {
+        return rsx! {};
+    };
+    let (source_pos, source_dir, target_pos, target_dir) = view.endpoints;
+
+    rsx! {
+        EdgeComponent {
+            edge: view.edge,
+            source_position: source_pos,
+            target_position: target_pos,
+            source_handle_direction: source_dir,
+            target_handle_direction: target_dir,
+            on_select: props.on_select,
+            on_double_click: props.on_double_click,
+            on_mouse_enter: props.on_mouse_enter,
+            on_mouse_leave: props.on_mouse_leave,
+            on_context_menu: props.on_context_menu,
+            editing_label: view.editing_label,
+            on_label_edit: props.on_label_edit,
+            on_label_commit: props.on_label_commit,
+            on_label_cancel: props.on_label_cancel,
+            show_label: view.show_label,
+        }
+    }
+}
+
+/// Revision counters of the mounted nodes and edges, by ID.
+#[derive(Default)]
+struct Revisions {
+    nodes: RefCell<HashMap<NodeId, Signal<u64>>>,
+    edges: RefCell<HashMap<EdgeId, Signal<u64>>>,
+}
+
+impl Revisions {
+    fn map(&self, kind: RevisionKind) -> &RefCell<HashMap<String, Signal<u64>>> {
+        match kind {
+            RevisionKind::Node => &self.nodes,
+            RevisionKind::Edge => &self.edges,
+        }
+    }
+
+    /// Bump the counters of the changed elements that are mounted.
+    fn bump(&self, changes: &Changes) {
+        let mut changed: Vec<Signal<u64>> = Vec::new();
+        {
+            let nodes = self.nodes.borrow();
+            let edges = self.edges.borrow();
+            if changes.all {
+                changed.extend(nodes.values().chain(edges.values()));
+            } else {
+                changed.extend(changes.nodes.iter().filter_map(|id| nodes.get(id)));
+                changed.extend(changes.edges.iter().filter_map(|id| edges.get(id)));
+            }
+        }
+        for mut revision in changed {
+            revision += 1;
+        }
+    }
+}
+
+/// Whether a revision counter belongs to a node or an edge.
+#[derive(Clone, Copy)]
+enum RevisionKind {
+    Node,
+    Edge,
+}
+
+/// Register a revision counter for a node or edge for as long as it is mounted. Reading
+/// it subscribes to changes the flow state records for the element.
+fn use_revision(kind: RevisionKind, id: &str) -> Signal<u64> {
+    let revisions = use_context::<Rc<Revisions>>();
+    let revision = use_signal(|| 0);
+    use_hook({
+        let revisions = revisions.clone();
+        let id = id.to_string();
+        move || revisions.map(kind).borrow_mut().insert(id, revision)
+    });
+    use_drop({
+        let id = id.to_string();
+        move || {
+            let mut map = revisions.map(kind).borrow_mut();
+            // Another element may have registered under the same ID since
+            if map.get(&id) == Some(&revision) {
+                map.remove(&id);
+            }
+        }
+    });
+    revision
+}
+
+/// State of a pressed node that may turn into a drag.
+#[derive(Clone)]
+struct NodeDrag<T: Clone + PartialEq + 'static> {
+    /// Node under the pointer.
+    node_id: NodeId,
+    /// Pointer position when the node was pressed, in screen coordinates.
+    pointer_start: Position,
+    /// Pointer position when the node was pressed, in flow coordinates.
+    flow_start: Position,
+    /// Moved nodes and their positions when the node was pressed.
+    starts: Vec<(NodeId, Position)>,
+    /// State before the drag, taken once the pointer passes the drag threshold.
+    snapshot: Option<FlowSnapshot<T>>,
+}
+
+/// Get the group a dragged node would be moved into on drop, if that changes its parent.
+fn reparent_target<T: Clone + Default + PartialEq + 'static>(
+    state: &FlowState<T>,
+    node_id: &str,
+) -> Option<NodeId> {
+    let node = state.get_node(node_id)?;
+    if node.extent_parent {
+        return None;
+    }
+    state
+        .find_drop_target(node_id)
+        .filter(|target| node.parent_id.as_ref() != Some(target))
+}
+
+/// Get the position and size of the flow container element.
+fn container_rect(element: &web_sys::Element) -> ContainerRect {
+    let rect = element.get_bounding_client_rect();
+    ContainerRect::new(rect.left(), rect.top(), rect.width(), rect.height())
+}
+
+/// Observes rendered node elements and the flow container, and records their sizes in
+/// the flow state.
+struct NodeMeasurer {
+    observer: Option<web_sys::ResizeObserver>,
+    /// Observed node elements, so they can be unobserved when the node unmounts.
+    nodes: RefCell<HashMap<NodeId, web_sys::Element>>,
+    _callback: Closure<dyn FnMut(js_sys::Array)>,
+}
+
+impl NodeMeasurer {
+    fn new<T: Clone + Default + PartialEq + 'static>(mut state: Signal<FlowState<T>>) -> Self {
+        // Observer callbacks arrive outside of the renderer, so re-enter our scope
+        let runtime = Runtime::current();
+        let scope = current_scope_id();
+        let callback = Closure::<dyn FnMut(js_sys::Array)>::new(move |entries: js_sys::Array| {
+            runtime.in_scope(scope, || {
+                for entry in entries.iter() {
+                    let Ok(entry) = entry.dyn_into::<web_sys::ResizeObserverEntry>() else {
+                        continue;
+                    };
+                    let target = entry.target();
+                    let Some(id) = target.get_attribute("data-id") else {
+                        // The only observed element without an id is the container
+                        let rect = container_rect(&target);
+                        if state.peek().container != rect {
+                            state.write().set_container(rect);
+                        }
+                        continue;
+                    };
+                    let Ok(element) = target.dyn_into::<web_sys::HtmlElement>() else {
+                        continue;
+                    };
+                    // Layout size, unaffected by the viewport zoom transform
+                    let width = element.offset_width() as f64;
+                    let height = element.offset_height() as f64;
+                    // Detached or hidden elements report zero
+                    if width <= 0.0 || height <= 0.0 {
+                        continue;
+                    }
+                    // Results can still arrive for nodes that were removed since, and a
+                    // collapsed group's compact box must not replace its expanded size
+                    let changed = state.peek().get_node(&id).is_some_and(|node| {
+                        !node.collapsed && node.measured != Some((width, height))
+                    });
+                    if changed {
+                        state.write().set_node_dimensions(&id, width, height);
+                    }
+                }
+            });
+        });
+        let observer = web_sys::ResizeObserver::new(callback.as_ref().unchecked_ref()).ok();
+        Self {
+            observer,
+            nodes: RefCell::new(HashMap::new()),
+            _callback: callback,
+        }
+    }
+
+    fn observe(&self, element: &web_sys::Element) {
+        if let Some(observer) = &self.observer {
+            observer.observe(element);
+        }
+    }
+
+    /// Observe the element of a node, replacing any element observed for it before.
+    fn observe_node(&self, id: NodeId, element: &web_sys::Element) {
+        let previous = self.nodes.borrow_mut().insert(id, element.clone());
+        if let Some(previous) = previous.filter(|previous| previous != element) {
+            self.unobserve(&previous);
+        }
+        self.observe(element);
+    }
+
+    /// Stop observing the element of a node.
+    fn unobserve_node(&self, id: &str) {
+        let element = self.nodes.borrow_mut().remove(id);
+        if let Some(element) = element {
+            self.unobserve(&element);
+        }
+    }
+
+    fn unobserve(&self, element: &web_sys::Element) {
+        if let Some(observer) = &self.observer {
+            observer.unobserve(element);
+        }
+    }
+}
+
+impl Drop for NodeMeasurer {
+    fn drop(&mut self) {
+        if let Some(observer) = &self.observer {
+            observer.disconnect();
+        }
+    }
+}
+
 /// Default CSS styles for the flow.
 pub const FLOW_STYLES: &str = r#"
 .dioxus-flow-container {
@@ -832,6 +1815,129 @@ pub const FLOW_STYLES: &str = r#"
     box-shadow: 0 0 0 0.5px #1a192b;
 }
 
+.dioxus-flow-node-group {
+    padding: 0;
+    min-width: 0;
+    min-height: 0;
+    background: rgba(240, 240, 240, 0.25);
+    border: 1px dashed #b1b1b7;
+    box-shadow: none;
+    text-align: left;
+}
+
+.dioxus-flow-node-group:hover {
+    box-shadow: none;
+}
+
+.dioxus-flow-node-group.dioxus-flow-node-selected {
+    border-color: #1a192b;
+}
+
+.dioxus-flow-node-collapsed {
+    overflow: hidden;
+}
+
+.dioxus-flow-node-placeholder {
+    display: flex;
+    align-items: center;
+    justify-content: center;
+    overflow: hidden;
+    background: #e2e8f0;
+    box-shadow: none;
+}
+
+.dioxus-flow-node-placeholder .dioxus-flow-node-label {
+    font-size: 24px;
+    white-space: nowrap;
+    overflow: hidden;
+    text-overflow: ellipsis;
+}
+
+.dioxus-flow-group-header {
+    display: flex;
+    align-items: center;
+    gap: 4px;
+    padding: 4px 8px;
+    font-size: 12px;
+    color: #555;
+}
+
+.dioxus-flow-group-label {
+    flex: 1;
+    overflow: hidden;
+    text-overflow: ellipsis;
+    white-space: nowrap;
+}
+
+.dioxus-flow-label-editor {
+    width: 100%;
+    box-sizing: border-box;
+    padding: 1px 4px;
+    font: inherit;
+    text-align: inherit;
+    border: 1px solid #1a192b;
+    border-radius: 3px;
+    outline: none;
+    background: white;
+}
+
+.dioxus-flow-group-button {
+    padding: 0 4px;
+    border: none;
+    background: transparent;
+    color: inherit;
+    cursor: pointer;
+    font-size: 12px;
+}
+
+.dioxus-flow-group-button:hover {
+    color: #1a192b;
+}
+
+.dioxus-flow-handle-proxy {
+    background: #b1b1b7;
+    pointer-events: none;
+}
+
+.dioxus-flow-breadcrumb {
+    position: absolute;
+    top: 10px;
+    left: 10px;
+    display: flex;
+    align-items: center;
+    gap: 4px;
+    padding: 4px 8px;
+    background: white;
+    border: 1px solid #ddd;
+    border-radius: 4px;
+    box-shadow: 0 2px 6px rgba(0, 0, 0, 0.1);
+    font-size: 12px;
+    z-index: 5;
+}
+
+.dioxus-flow-breadcrumb-item {
+    padding: 0;
+    border: none;
+    background: transparent;
+    color: #3b82f6;
+    cursor: pointer;
+    font: inherit;
+}
+
+.dioxus-flow-breadcrumb-current {
+    color: #1a192b;
+    cursor: default;
+}
+
+.dioxus-flow-breadcrumb-separator {
+    color: #b1b1b7;
+}
+
+.dioxus-flow-node-drop-target {
+    border-color: #3b82f6;
+    background: rgba(59, 130, 246, 0.08);
+}
+
 .dioxus-flow-node-dragging {
     cursor: grabbing;
     opacity: 0.8;
@@ -886,6 +1992,10 @@ pub const FLOW_STYLES: &str = r#"
     transition: stroke 0.2s;
 }
 
+.dioxus-flow-edge-hovered .dioxus-flow-edge-path {
+    stroke: #777;
+}
+
 .dioxus-flow-edge-selected .dioxus-flow-edge-path {
     stroke: #1a192b;
 }
@@ -901,7 +2011,40 @@ pub const FLOW_STYLES: &str = r#"
     }
 }
 
+.dioxus-flow-edge-pulse .dioxus-flow-edge-path {
+    animation: dioxus-flow-pulse 1.5s ease-in-out infinite;
+}
+
+@keyframes dioxus-flow-pulse {
+    50% {
+        stroke-opacity: 0.4;
+        stroke-width: 4px;
+    }
+}
+
+.dioxus-flow-edge-particle {
+    pointer-events: none;
+}
+
+.dioxus-flow-edge-status-active .dioxus-flow-edge-path {
+    stroke: #3b82f6;
+}
+
+.dioxus-flow-edge-status-error .dioxus-flow-edge-path {
+    stroke: #ef4444;
+    stroke-dasharray: 6 3;
+}
+
+.dioxus-flow-edge-status-active .dioxus-flow-edge-particle {
+    fill: #3b82f6;
+}
+
+.dioxus-flow-edge-status-error .dioxus-flow-edge-particle {
+    fill: #ef4444;
+}
+
 .dioxus-flow-edge-label {
+    pointer-events: all;
     background: white;
     padding: 2px 4px;
     border-radius: 3px;
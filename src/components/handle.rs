@@ -53,8 +53,8 @@ pub fn Handle(props: HandleProps) -> Element {
     };
 
     let position = props.position;
-    let on_connect_start = props.on_connect_start.clone();
-    let on_connect_end = props.on_connect_end.clone();
+    let on_connect_start = props.on_connect_start;
+    let on_connect_end = props.on_connect_end;
 
     rsx! {
         div {
@@ -0,0 +1,99 @@
+//! Alignment guides shown while dragging nodes.
+
+use crate::types::{HelperLines, Viewport};
+use dioxus::prelude::*;
+
+/// Helper lines component props.
+#[derive(Props, Clone, PartialEq)]
+pub struct HelperLinesRendererProps {
+    /// Guides to draw, in flow coordinates.
+    pub lines: HelperLines,
+    /// Current viewport.
+    pub viewport: Viewport,
+    /// Color of the alignment lines.
+    #[props(default = "#ff0071".to_string())]
+    pub color: String,
+    /// Color of the equal-spacing indicators.
+    #[props(default = "#0ea5e9".to_string())]
+    pub spacing_color: String,
+}
+
+/// Draws alignment lines across the canvas and equal-spacing indicators between nodes.
+#[component]
+pub fn HelperLinesRenderer(props: HelperLinesRendererProps) -> Element {
+    if props.lines.is_empty() {
+        return rsx! {};
+    }
+
+    let vp = props.viewport;
+    let vertical = props.lines.vertical.map(|x| vp.flow_to_screen(x, 0.0).x);
+    let horizontal = props.lines.horizontal.map(|y| vp.flow_to_screen(0.0, y).y);
+    let spacing: Vec<(f64, f64, f64, f64)> = props
+        .lines
+        .spacing
+        .iter()
+        .map(|(from, to)| {
+            let from = vp.flow_to_screen(from.x, from.y);
+            let to = vp.flow_to_screen(to.x, to.y);
+            (from.x, from.y, to.x, to.y)
+        })
+        .collect();
+
+    rsx! {
+        svg {
+            class: "dioxus-flow-helper-lines",
+            style: "position: absolute; top: 0; left: 0; width: 100%; height: 100%; pointer-events: none;",
+
+            if let Some(x) = vertical {
+                line {
+                    x1: "{x}",
+                    y1: "0",
+                    x2: "{x}",
+                    y2: "100%",
+                    stroke: "{props.color}",
+                    stroke_width: "1",
+                }
+            }
+            if let Some(y) = horizontal {
+                line {
+                    x1: "0",
+                    y1: "{y}",
+                    x2: "100%",
+                    y2: "{y}",
+                    stroke: "{props.color}",
+                    stroke_width: "1",
+                }
+            }
+            for (x1, y1, x2, y2) in spacing {
+                line {
+                    class: "dioxus-flow-helper-spacing",
+                    x1: "{x1}",
+                    y1: "{y1}",
+                    x2: "{x2}",
+                    y2: "{y2}",
+                    stroke: "{props.spacing_color}",
+                    stroke_width: "1",
+                    marker_start: "url(#dioxus-flow-spacing-tick)",
+                    marker_end: "url(#dioxus-flow-spacing-tick)",
+                }
+            }
+            defs {
+                marker {
+                    id: "dioxus-flow-spacing-tick",
+                    view_box: "0 0 2 10",
+                    marker_width: "2",
+                    marker_height: "10",
+                    ref_x: "1",
+                    ref_y: "5",
+                    orient: "auto",
+                    marker_units: "userSpaceOnUse",
+                    path {
+                        d: "M 1 0 L 1 10",
+                        stroke: "{props.spacing_color}",
+                        stroke_width: "1",
+                    }
+                }
+            }
+        }
+    }
+}
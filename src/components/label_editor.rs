@@ -0,0 +1,75 @@
+//! Inline text input for editing node and edge labels.
+
+use dioxus::prelude::*;
+use dioxus::web::WebEventExt;
+use wasm_bindgen::JsCast;
+
+/// Label editor component props.
+#[derive(Props, Clone, PartialEq)]
+pub struct LabelEditorProps {
+    /// Text the editor starts with.
+    pub value: String,
+    /// Callback with the edited text on Enter or when the editor loses focus.
+    #[props(default)]
+    pub on_commit: Option<EventHandler<String>>,
+    /// Callback when editing is cancelled with Escape.
+    #[props(default)]
+    pub on_cancel: Option<EventHandler<()>>,
+}
+
+/// Text input that takes focus when shown and commits on Enter or blur.
+#[component]
+pub fn LabelEditor(props: LabelEditorProps) -> Element {
+    let mut text = use_signal(|| props.value.clone());
+    // Enter/Escape remove the editor, which must not commit a second time on blur
+    let mut done = use_signal(|| false);
+    let on_commit = props.on_commit;
+    let on_cancel = props.on_cancel;
+
+    let mut commit = move || {
+        if *done.peek() {
+            return;
+        }
+        done.set(true);
+        if let Some(handler) = &on_commit {
+            handler.call(text.peek().clone());
+        }
+    };
+
+    rsx! {
+        input {
+            class: "dioxus-flow-label-editor",
+            r#type: "text",
+            value: "{text}",
+            onmounted: move |evt: MountedEvent| {
+                if let Ok(input) = evt.as_web_event().dyn_into::<web_sys::HtmlInputElement>() {
+                    let _ = input.focus();
+                    input.select();
+                }
+            },
+            oninput: move |evt: FormEvent| text.set(evt.value()),
+            onkeydown: move |evt: KeyboardEvent| {
+                // Keep flow shortcuts (Backspace, arrows, copy/paste) away from the canvas
+                evt.stop_propagation();
+                match evt.key() {
+                    Key::Enter => {
+                        evt.prevent_default();
+                        commit();
+                    }
+                    Key::Escape => {
+                        evt.prevent_default();
+                        done.set(true);
+                        if let Some(handler) = &on_cancel {
+                            handler.call(());
+                        }
+                    }
+                    _ => {}
+                }
+            },
+            onblur: move |_| commit(),
+            onmousedown: move |evt: MouseEvent| evt.stop_propagation(),
+            onclick: move |evt: MouseEvent| evt.stop_propagation(),
+            ondoubleclick: move |evt: MouseEvent| evt.stop_propagation(),
+        }
+    }
+}
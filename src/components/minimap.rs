@@ -1,8 +1,10 @@
 //! Minimap component for flow overview navigation.
 
 use crate::hooks::FlowState;
-use crate::types::Viewport;
+use crate::types::{NodeId, SelectionRect, Viewport};
 use dioxus::prelude::*;
+use dioxus::web::WebEventExt;
+use wasm_bindgen::JsCast;
 
 /// Minimap position on the screen.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -49,14 +51,39 @@ pub struct MiniMapProps<T: Clone + PartialEq + 'static> {
 }
 
 /// Minimap component showing an overview of the flow.
+///
+/// Nodes are drawn in flow coordinates and scaled by the SVG view box, so moving one
+/// node or panning the flow only re-renders what changed.
 #[component]
 pub fn MiniMap<T: Clone + Default + PartialEq + 'static>(props: MiniMapProps<T>) -> Element {
     let mut state = props.state;
-    let nodes = state.read().nodes.clone();
-    let viewport = state.read().viewport;
-
+    let node_ids = use_memo(move || {
+        let state_ref = state.read();
+        state_ref
+            .nodes
+            .iter()
+            .filter(|n| state_ref.is_node_visible(&n.id))
+            .map(|n| n.id.clone())
+            .collect::<Vec<NodeId>>()
+    });
     // Calculate bounds of all nodes
-    let (min_x, min_y, max_x, max_y) = calculate_bounds(&nodes);
+    let bounds = use_memo(move || {
+        let state_ref = state.read();
+        calculate_bounds(
+            state_ref
+                .nodes
+                .iter()
+                .filter(|n| state_ref.is_node_visible(&n.id))
+                .map(|n| n.rect()),
+        )
+    });
+    let view = use_memo(move || {
+        let state_ref = state.read();
+        (state_ref.viewport, state_ref.container)
+    });
+    let (viewport, container) = view();
+
+    let (min_x, min_y, max_x, max_y) = bounds();
     let content_width = (max_x - min_x).max(100.0);
     let content_height = (max_y - min_y).max(100.0);
 
@@ -76,9 +103,8 @@ pub fn MiniMap<T: Clone + Default + PartialEq + 'static>(props: MiniMapProps<T>)
     // This represents what's currently visible in the main flow
     let vp_x = (-viewport.x / viewport.zoom - padded_min_x) * scale;
     let vp_y = (-viewport.y / viewport.zoom - padded_min_y) * scale;
-    // Assuming container is roughly 800x600 for now (would need actual dimensions)
-    let vp_width = (800.0 / viewport.zoom) * scale;
-    let vp_height = (600.0 / viewport.zoom) * scale;
+    let vp_width = (container.width / viewport.zoom) * scale;
+    let vp_height = (container.height / viewport.zoom) * scale;
 
     let position_style = match props.position {
         MiniMapPosition::TopLeft => "top: 10px; left: 10px;",
@@ -90,26 +116,31 @@ pub fn MiniMap<T: Clone + Default + PartialEq + 'static>(props: MiniMapProps<T>)
     let width = props.width;
     let height = props.height;
     let pannable = props.pannable;
-    let on_viewport_change = props.on_viewport_change.clone();
+    let on_viewport_change = props.on_viewport_change;
 
     let on_click = move |evt: MouseEvent| {
         if !pannable {
             return;
         }
 
+        // Click position within the minimap
         let coords = evt.client_coordinates();
-        // Convert click position to flow coordinates
-        // This is a simplified version - would need element bounds for accuracy
-        let click_x = coords.x;
-        let click_y = coords.y;
-
-        // Calculate new viewport center
-        let flow_x = (click_x / scale) + padded_min_x;
-        let flow_y = (click_y / scale) + padded_min_y;
+        let Some(bounds) = evt
+            .as_web_event()
+            .current_target()
+            .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+            .map(|el| el.get_bounding_client_rect())
+        else {
+            return;
+        };
+        let flow_x = ((coords.x - bounds.left()) / scale) + padded_min_x;
+        let flow_y = ((coords.y - bounds.top()) / scale) + padded_min_y;
 
+        // Centre the main view on the clicked point
+        let center = container.center();
         let new_viewport = Viewport {
-            x: -flow_x * viewport.zoom + 400.0, // Assuming 800px container
-            y: -flow_y * viewport.zoom + 300.0, // Assuming 600px container
+            x: center.x - flow_x * viewport.zoom,
+            y: center.y - flow_y * viewport.zoom,
             zoom: viewport.zoom,
         };
 
@@ -129,17 +160,18 @@ pub fn MiniMap<T: Clone + Default + PartialEq + 'static>(props: MiniMapProps<T>)
                 height: "{height}",
                 onclick: on_click,
 
-                // Render nodes as simple rectangles
-                for node in nodes.iter() {
-                    rect {
-                        x: "{(node.position.x - padded_min_x) * scale}",
-                        y: "{(node.position.y - padded_min_y) * scale}",
-                        width: "{node.width.unwrap_or(150.0) * scale}",
-                        height: "{node.height.unwrap_or(40.0) * scale}",
+                // Render nodes as simple rectangles, scaled from flow coordinates
+                svg {
+                    width: "{width}",
+                    height: "{height}",
+                    view_box: "{padded_min_x} {padded_min_y} {width / scale} {height / scale}",
+                    preserve_aspect_ratio: "xMinYMin meet",
+                    g {
                         fill: "{props.node_color}",
                         stroke: "{props.node_stroke_color}",
-                        stroke_width: "1",
-                        rx: "2",
+                        for id in node_ids.read().iter() {
+                            MiniMapNode { key: "{id}", state: state, id: id.clone() }
+                        }
                     }
                 }
 
@@ -190,26 +222,52 @@ pub fn MiniMap<T: Clone + Default + PartialEq + 'static>(props: MiniMapProps<T>)
     }
 }
 
-/// Calculate the bounding box of all nodes.
-fn calculate_bounds<T: Clone + PartialEq>(nodes: &[crate::types::Node<T>]) -> (f64, f64, f64, f64) {
-    if nodes.is_empty() {
-        return (0.0, 0.0, 100.0, 100.0);
+/// Props of a node drawn by `MiniMap`.
+#[derive(Props, Clone, PartialEq)]
+struct MiniMapNodeProps<T: Clone + PartialEq + 'static> {
+    state: Signal<FlowState<T>>,
+    id: NodeId,
+}
+
+/// A node drawn by `MiniMap`, re-rendered only when its bounds change.
+#[component]
+fn MiniMapNode<T: Clone + Default + PartialEq + 'static>(props: MiniMapNodeProps<T>) -> Element {
+    let state = props.state;
+    let id = props.id.clone();
+    let rect = use_memo(use_reactive!(|id| state.read().get_node(&id).map(|n| n.rect())));
+    let Some(rect) = rect() else {
+        return rsx! {};
+    };
+
+    rsx! {
+        rect {
+            x: "{rect.x}",
+            y: "{rect.y}",
+            width: "{rect.width}",
+            height: "{rect.height}",
+            stroke_width: "1",
+            vector_effect: "non-scaling-stroke",
+            rx: "2",
+        }
     }
+}
 
+/// Calculate the bounding box of the given node bounds.
+fn calculate_bounds(rects: impl Iterator<Item = SelectionRect>) -> (f64, f64, f64, f64) {
     let mut min_x = f64::MAX;
     let mut min_y = f64::MAX;
     let mut max_x = f64::MIN;
     let mut max_y = f64::MIN;
 
-    for node in nodes {
-        let w = node.width.unwrap_or(150.0);
-        let h = node.height.unwrap_or(40.0);
-
-        min_x = min_x.min(node.position.x);
-        min_y = min_y.min(node.position.y);
-        max_x = max_x.max(node.position.x + w);
-        max_y = max_y.max(node.position.y + h);
+    for rect in rects {
+        min_x = min_x.min(rect.x);
+        min_y = min_y.min(rect.y);
+        max_x = max_x.max(rect.x + rect.width);
+        max_y = max_y.max(rect.y + rect.height);
     }
 
+    if min_x > max_x {
+        return (0.0, 0.0, 100.0, 100.0);
+    }
     (min_x, min_y, max_x, max_y)
 }
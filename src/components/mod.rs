@@ -5,6 +5,10 @@ pub mod controls;
 pub mod edge;
 pub mod flow;
 pub mod handle;
+pub mod helper_lines;
+pub mod label_editor;
 pub mod minimap;
 pub mod node;
+pub mod node_resizer;
 pub mod selection_box;
+pub mod subflow_breadcrumb;
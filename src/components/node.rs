@@ -1,7 +1,10 @@
 //! Node component for the flow.
 
-use crate::types::{HandleKind, HandlePosition, Node, NodeId, Position};
+use crate::components::label_editor::LabelEditor;
+use crate::types::{DetailLevel, HandleKind, HandlePosition, Node, NodeId, Position};
+use crate::utils::target_matches;
 use dioxus::prelude::*;
+use dioxus::web::WebEventExt;
 
 /// Node component props.
 #[derive(Props, Clone, PartialEq)]
@@ -13,6 +16,9 @@ pub struct NodeComponentProps<T: Clone + PartialEq + 'static> {
     /// Whether the node is currently being dragged.
     #[props(default)]
     pub dragging: bool,
+    /// Whether the node is highlighted as the drop target of a dragged node.
+    #[props(default)]
+    pub drop_target: bool,
     /// Callback when node is selected.
     #[props(default)]
     pub on_select: Option<EventHandler<NodeId>>,
@@ -31,6 +37,37 @@ pub struct NodeComponentProps<T: Clone + PartialEq + 'static> {
     /// Callback when connection ends at a handle.
     #[props(default)]
     pub on_connect_end: Option<EventHandler<(NodeId, HandlePosition)>>,
+    /// Class marking elements inside the node that must not start a drag.
+    #[props(default = "nodrag".to_string())]
+    pub no_drag_class: String,
+    /// Proxy handles (side, is_source) for edges re-routed to this collapsed node.
+    #[props(default)]
+    pub proxy_handles: Vec<(HandlePosition, bool)>,
+    /// Callback when a group's collapse toggle is clicked.
+    #[props(default)]
+    pub on_toggle_collapse: Option<EventHandler<NodeId>>,
+    /// Callback when a group's open button is clicked.
+    #[props(default)]
+    pub on_open_subflow: Option<EventHandler<NodeId>>,
+    /// Whether the default label is being edited in place.
+    #[props(default)]
+    pub editing_label: bool,
+    /// Callback when the default label is double-clicked to edit it.
+    #[props(default)]
+    pub on_label_edit: Option<EventHandler<NodeId>>,
+    /// Callback with the edited label text.
+    #[props(default)]
+    pub on_label_commit: Option<EventHandler<String>>,
+    /// Callback when label editing is cancelled.
+    #[props(default)]
+    pub on_label_cancel: Option<EventHandler<()>>,
+    /// Detail level to draw the node at. Handles are only shown at full detail, and
+    /// placeholders show just the label.
+    #[props(default)]
+    pub detail: DetailLevel,
+    /// Callback when the node element is mounted (used for measuring).
+    #[props(default)]
+    pub on_mounted: Option<EventHandler<MountedEvent>>,
     /// Custom node renderer.
     #[props(default)]
     pub children: Element,
@@ -52,19 +89,43 @@ pub fn NodeComponent<T: Clone + PartialEq + 'static>(props: NodeComponentProps<T
     } else {
         ""
     };
+    let drop_target_class = if props.drop_target {
+        "dioxus-flow-node-drop-target"
+    } else {
+        ""
+    };
+    let collapsed_class = if node.collapsed {
+        "dioxus-flow-node-collapsed"
+    } else {
+        ""
+    };
+    let placeholder = props.detail == DetailLevel::Placeholder;
+    let placeholder_class = if placeholder {
+        "dioxus-flow-node-placeholder"
+    } else {
+        ""
+    };
+    let show_handles = props.detail == DetailLevel::Full;
 
-    // Build style with explicit dimensions if set
+    // Build style with explicit dimensions if set; collapsed nodes use their compact size
+    // and placeholders keep the size the full node was measured at
     let dimensions = match (node.width, node.height) {
+        _ if node.collapsed || placeholder => {
+            let (w, h) = node.dimensions();
+            format!(" width: {}px; height: {}px;", w, h)
+        }
         (Some(w), Some(h)) => format!(" width: {}px; height: {}px;", w, h),
         (Some(w), None) => format!(" width: {}px;", w),
         (None, Some(h)) => format!(" height: {}px;", h),
         (None, None) => String::new(),
     };
 
+    // Nodes are laid out flat, so children are placed at their absolute position
+    let position = node.absolute_position();
     let style = format!(
         "position: absolute; left: {}px; top: {}px; box-sizing: border-box; pointer-events: auto;{}{}",
-        node.position.x,
-        node.position.y,
+        position.x,
+        position.y,
         dimensions,
         node.style
             .iter()
@@ -72,18 +133,61 @@ pub fn NodeComponent<T: Clone + PartialEq + 'static>(props: NodeComponentProps<T
             .collect::<String>()
     );
 
-    let on_select = props.on_select.clone();
-    let on_drag_start = props.on_drag_start.clone();
-    let on_connect_start = props.on_connect_start.clone();
-    let on_connect_end = props.on_connect_end.clone();
+    let on_select = props.on_select;
+    let on_drag_start = props.on_drag_start;
+    let on_connect_start = props.on_connect_start;
+    let on_connect_end = props.on_connect_end;
+    let on_mounted = props.on_mounted;
+    let on_toggle_collapse = props.on_toggle_collapse;
+    let on_open_subflow = props.on_open_subflow;
     let draggable = node.draggable;
     let connectable = node.connectable;
+    // Form controls keep their own mouse behavior, as do `nodrag` elements
+    let no_drag_selector = format!(".{}, input, textarea, select, button", props.no_drag_class);
+    let drag_handle = node.drag_handle.clone();
+
+    // Default label, swapped for an inline editor while it is being renamed
+    let has_custom_content = props
+        .children
+        .as_ref()
+        .is_ok_and(|content| *content != VNode::default());
+    let on_label_edit = props.on_label_edit;
+    let label_view = if props.editing_label {
+        rsx! {
+            LabelEditor {
+                value: node.label.clone().unwrap_or_default(),
+                on_commit: props.on_label_commit,
+                on_cancel: props.on_label_cancel,
+            }
+        }
+    } else {
+        rsx! {
+            span {
+                class: "dioxus-flow-node-label",
+                ondoubleclick: {
+                    let node_id = node_id.clone();
+                    move |evt: MouseEvent| {
+                        if let Some(handler) = &on_label_edit {
+                            evt.stop_propagation();
+                            handler.call(node_id.clone());
+                        }
+                    }
+                },
+                {node.label.as_ref().unwrap_or(&node.id).clone()}
+            }
+        }
+    };
 
     rsx! {
         div {
-            class: "dioxus-flow-node dioxus-flow-node-{node.node_type} {selected_class} {dragging_class} {node.class}",
+            class: "dioxus-flow-node dioxus-flow-node-{node.node_type} {selected_class} {dragging_class} {drop_target_class} {collapsed_class} {placeholder_class} {node.class}",
             style: "{style}",
             "data-id": "{node.id}",
+            onmounted: move |evt: MountedEvent| {
+                if let Some(handler) = &on_mounted {
+                    handler.call(evt);
+                }
+            },
             onclick: {
                 let node_id = node_id.clone();
                 move |evt: MouseEvent| {
@@ -98,6 +202,16 @@ pub fn NodeComponent<T: Clone + PartialEq + 'static>(props: NodeComponentProps<T
                 move |evt: MouseEvent| {
                     if draggable {
                         evt.stop_propagation();
+                        let web_evt = evt.as_web_event();
+                        let (target, node_el) = (web_evt.target(), web_evt.current_target());
+                        if target_matches(target.clone(), node_el.clone(), &no_drag_selector) {
+                            return;
+                        }
+                        if let Some(selector) = &drag_handle {
+                            if !target_matches(target, node_el, selector) {
+                                return;
+                            }
+                        }
                         if let Some(handler) = &on_drag_start {
                             let coords = evt.client_coordinates();
                             handler.call((node_id.clone(), Position::new(coords.x, coords.y)));
@@ -106,11 +220,10 @@ pub fn NodeComponent<T: Clone + PartialEq + 'static>(props: NodeComponentProps<T
                 }
             },
             // Render handles from node.handles
-            if connectable && !node.handles.is_empty() {
+            if connectable && show_handles && !node.handles.is_empty() {
                 for handle in node.handles.iter() {
                     {
-                        let node_width = node.width.unwrap_or(150.0);
-                        let node_height = node.height.unwrap_or(40.0);
+                        let (node_width, node_height) = node.dimensions();
                         let handle_id = handle.id.clone();
                         let handle_pos = handle.position;
                         let handle_kind = handle.kind;
@@ -166,7 +279,6 @@ pub fn NodeComponent<T: Clone + PartialEq + 'static>(props: NodeComponentProps<T
                                 "data-handle-position": "{pos_class}",
                                 onmousedown: {
                                     let node_id = node_id.clone();
-                                    let handle_pos = handle_pos;
                                     move |evt: MouseEvent| {
                                         if handle_kind == HandleKind::Source {
                                             evt.stop_propagation();
@@ -178,7 +290,6 @@ pub fn NodeComponent<T: Clone + PartialEq + 'static>(props: NodeComponentProps<T
                                 },
                                 onmouseup: {
                                     let node_id = node_id.clone();
-                                    let handle_pos = handle_pos;
                                     move |evt: MouseEvent| {
                                         if handle_kind == HandleKind::Target {
                                             evt.stop_propagation();
@@ -192,7 +303,7 @@ pub fn NodeComponent<T: Clone + PartialEq + 'static>(props: NodeComponentProps<T
                         }
                     }
                 }
-            } else if connectable {
+            } else if connectable && show_handles {
                 // Fallback: render default top/bottom handles
                 div {
                     class: "dioxus-flow-handle dioxus-flow-handle-bottom dioxus-flow-handle-source",
@@ -225,13 +336,81 @@ pub fn NodeComponent<T: Clone + PartialEq + 'static>(props: NodeComponentProps<T
                     },
                 }
             }
+            // Proxy handles for edges re-routed from hidden descendants
+            for (side, is_source) in props.proxy_handles.iter().copied().filter(|_| show_handles) {
+                {
+                    let (pos_class, style_pos) = match side {
+                        HandlePosition::Top => ("top", "top: 0; left: 50%; transform: translate(-50%, -50%);"),
+                        HandlePosition::Bottom => ("bottom", "bottom: 0; left: 50%; transform: translate(-50%, 50%);"),
+                        HandlePosition::Left => ("left", "left: 0; top: 50%; transform: translate(-50%, -50%);"),
+                        HandlePosition::Right => ("right", "right: 0; top: 50%; transform: translate(50%, -50%);"),
+                    };
+                    let kind_class = if is_source { "source" } else { "target" };
+                    rsx! {
+                        div {
+                            key: "proxy-{pos_class}-{kind_class}",
+                            class: "dioxus-flow-handle dioxus-flow-handle-{pos_class} dioxus-flow-handle-{kind_class} dioxus-flow-handle-proxy",
+                            style: "position: absolute; {style_pos}",
+                        }
+                    }
+                }
+            }
+            // Group header with collapse and drill-down buttons
+            if node.is_group() && !placeholder {
+                div {
+                    class: "dioxus-flow-group-header",
+                    span {
+                        class: "dioxus-flow-group-label",
+                        {label_view.clone()}
+                    }
+                    if on_toggle_collapse.is_some() {
+                        button {
+                            class: "dioxus-flow-group-button",
+                            title: if node.collapsed { "Expand" } else { "Collapse" },
+                            onmousedown: move |evt: MouseEvent| evt.stop_propagation(),
+                            onclick: {
+                                let node_id = node_id.clone();
+                                move |evt: MouseEvent| {
+                                    evt.stop_propagation();
+                                    if let Some(handler) = &on_toggle_collapse {
+                                        handler.call(node_id.clone());
+                                    }
+                                }
+                            },
+                            if node.collapsed { "▸" } else { "▾" }
+                        }
+                    }
+                    if on_open_subflow.is_some() {
+                        button {
+                            class: "dioxus-flow-group-button",
+                            title: "Open",
+                            onmousedown: move |evt: MouseEvent| evt.stop_propagation(),
+                            onclick: {
+                                let node_id = node_id.clone();
+                                move |evt: MouseEvent| {
+                                    evt.stop_propagation();
+                                    if let Some(handler) = &on_open_subflow {
+                                        handler.call(node_id.clone());
+                                    }
+                                }
+                            },
+                            "⤢"
+                        }
+                    }
+                }
+            }
             // Node content - use children if provided, otherwise show label
             div {
                 class: "dioxus-flow-node-content",
-                if props.children.is_ok() {
+                if placeholder {
+                    span {
+                        class: "dioxus-flow-node-label",
+                        {node.label.as_ref().unwrap_or(&node.id).clone()}
+                    }
+                } else if has_custom_content {
                     {props.children}
-                } else {
-                    {node.label.as_ref().unwrap_or(&node.id).clone()}
+                } else if !node.is_group() {
+                    {label_view}
                 }
             }
         }
@@ -0,0 +1,256 @@
+//! Node resizer component with corner and edge grips.
+
+use crate::hooks::{FlowSnapshot, FlowState};
+use crate::types::{
+    FlowEvent, NodeId, Position, ResizeConstraints, ResizeDirection, ResizeParams,
+};
+use dioxus::prelude::*;
+use dioxus::web::WebEventExt;
+use wasm_bindgen::JsCast;
+
+/// State of an in-progress resize gesture.
+#[derive(Clone)]
+struct ResizeGesture<T: Clone + PartialEq + 'static> {
+    direction: ResizeDirection,
+    pointer_start: Position,
+    start: ResizeParams,
+    snapshot: FlowSnapshot<T>,
+}
+
+/// Node resizer component props.
+#[derive(Props, Clone, PartialEq)]
+pub struct NodeResizerProps<T: Clone + PartialEq + 'static> {
+    /// Flow state containing the node.
+    pub state: Signal<FlowState<T>>,
+    /// ID of the node to resize.
+    pub node_id: NodeId,
+    /// Whether the grips are shown.
+    #[props(default = true)]
+    pub visible: bool,
+    /// Minimum width.
+    #[props(default = 10.0)]
+    pub min_width: f64,
+    /// Minimum height.
+    #[props(default = 10.0)]
+    pub min_height: f64,
+    /// Maximum width.
+    #[props(default = f64::MAX)]
+    pub max_width: f64,
+    /// Maximum height.
+    #[props(default = f64::MAX)]
+    pub max_height: f64,
+    /// Whether to keep the aspect ratio while resizing.
+    #[props(default = false)]
+    pub keep_aspect_ratio: bool,
+    /// Color of the grips and outline.
+    #[props(default = "#1a192b".to_string())]
+    pub color: String,
+    /// Callback when a resize starts.
+    #[props(default)]
+    pub on_resize_start: Option<EventHandler<ResizeParams>>,
+    /// Callback while resizing.
+    #[props(default)]
+    pub on_resize: Option<EventHandler<ResizeParams>>,
+    /// Callback when a resize ends.
+    #[props(default)]
+    pub on_resize_end: Option<EventHandler<ResizeParams>>,
+}
+
+/// Resizer overlay for a node. Render it inside the node content (e.g. from `node_render`).
+///
+/// The grips are styled by [`NODE_RESIZER_STYLES`], which isn't part of `FLOW_STYLES`;
+/// include it in the page alongside them.
+#[component]
+pub fn NodeResizer<T: Clone + Default + PartialEq + 'static>(props: NodeResizerProps<T>) -> Element {
+    let mut state = props.state;
+    let mut gesture: Signal<Option<ResizeGesture<T>>> = use_signal(|| None);
+
+    if !props.visible {
+        return rsx! {};
+    }
+
+    let constraints = ResizeConstraints {
+        min_width: props.min_width,
+        min_height: props.min_height,
+        max_width: props.max_width,
+        max_height: props.max_height,
+        keep_aspect_ratio: props.keep_aspect_ratio,
+    };
+    let on_resize_start = props.on_resize_start;
+    let on_resize = props.on_resize;
+    let on_resize_end = props.on_resize_end;
+
+    // Ends the gesture on release, and also when the browser cancels it or takes the
+    // pointer capture away, so the resizer is never left active
+    let mut finish_resize = move |node_id: &NodeId| {
+        let Some(active) = gesture.take() else {
+            return;
+        };
+        let end = state.read().get_node(node_id).map(|n| {
+            let (width, height) = n.dimensions();
+            ResizeParams {
+                position: n.position,
+                width,
+                height,
+            }
+        });
+        if let Some(end) = end {
+            // One undo entry per gesture, and only if something changed
+            if end != active.start {
+                state.write().push_history(active.snapshot);
+            }
+            if let Some(handler) = &on_resize_end {
+                handler.call(end);
+            }
+        }
+        state.write().emit_event(FlowEvent::NodeResizeEnd(node_id.clone()));
+    };
+
+    rsx! {
+        div {
+            class: "dioxus-flow-node-resizer",
+            style: "position: absolute; top: 0; left: 0; right: 0; bottom: 0; pointer-events: none; border: 1px solid {props.color};",
+
+            for direction in ResizeDirection::ALL {
+                div {
+                    key: "{direction.class_name()}",
+                    class: "dioxus-flow-resize-grip dioxus-flow-resize-grip-{direction.class_name()}",
+                    class: if direction.is_corner() { "dioxus-flow-resize-grip-corner" } else { "dioxus-flow-resize-grip-edge" },
+                    style: "cursor: {direction.cursor()}; --dioxus-flow-resizer-color: {props.color};",
+                    // Keep the node from starting a drag
+                    onmousedown: move |evt: MouseEvent| {
+                        evt.stop_propagation();
+                    },
+                    onpointerdown: {
+                        let node_id = props.node_id.clone();
+                        move |evt: PointerEvent| {
+                            evt.stop_propagation();
+                            // Capture the pointer so moves outside the grip still reach us
+                            if let Some(target) = evt
+                                .as_web_event()
+                                .current_target()
+                                .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+                            {
+                                let _ = target.set_pointer_capture(evt.pointer_id());
+                            }
+
+                            let start = state.read().get_node(&node_id).map(|n| {
+                                let (width, height) = n.dimensions();
+                                ResizeParams {
+                                    position: n.position,
+                                    width,
+                                    height,
+                                }
+                            });
+                            if let Some(start) = start {
+                                let coords = evt.client_coordinates();
+                                let snapshot = state.read().snapshot();
+                                gesture.set(Some(ResizeGesture {
+                                    direction,
+                                    pointer_start: Position::new(coords.x, coords.y),
+                                    start,
+                                    snapshot,
+                                }));
+                                if let Some(handler) = &on_resize_start {
+                                    handler.call(start);
+                                }
+                                state.write().emit_event(FlowEvent::NodeResizeStart(node_id.clone()));
+                            }
+                        }
+                    },
+                    onpointermove: {
+                        let node_id = props.node_id.clone();
+                        move |evt: PointerEvent| {
+                            let Some(active) = gesture.read().clone() else {
+                                return;
+                            };
+                            let coords = evt.client_coordinates();
+                            let zoom = state.read().viewport.zoom;
+                            let dx = (coords.x - active.pointer_start.x) / zoom;
+                            let dy = (coords.y - active.pointer_start.y) / zoom;
+
+                            let params = state.write().resize_node(
+                                &node_id,
+                                active.direction,
+                                active.start,
+                                dx,
+                                dy,
+                                &constraints,
+                            );
+                            if let Some(params) = params {
+                                if let Some(handler) = &on_resize {
+                                    handler.call(params);
+                                }
+                                state.write().emit_event(FlowEvent::NodeResize {
+                                    id: node_id.clone(),
+                                    position: params.position,
+                                    width: params.width,
+                                    height: params.height,
+                                });
+                            }
+                        }
+                    },
+                    onpointerup: {
+                        let node_id = props.node_id.clone();
+                        move |_evt: PointerEvent| finish_resize(&node_id)
+                    },
+                    onpointercancel: {
+                        let node_id = props.node_id.clone();
+                        move |_evt: PointerEvent| finish_resize(&node_id)
+                    },
+                    onlostpointercapture: {
+                        let node_id = props.node_id.clone();
+                        move |_evt: PointerEvent| finish_resize(&node_id)
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// CSS styles for the node resizer. Include them in the page, next to `FLOW_STYLES`,
+/// when rendering `NodeResizer`.
+pub const NODE_RESIZER_STYLES: &str = r#"
+.dioxus-flow-resize-grip {
+    position: absolute;
+    pointer-events: auto;
+    box-sizing: border-box;
+}
+
+.dioxus-flow-resize-grip-corner {
+    width: 8px;
+    height: 8px;
+    background: white;
+    border: 1px solid var(--dioxus-flow-resizer-color);
+    border-radius: 1px;
+    transform: translate(-50%, -50%);
+    z-index: 1;
+}
+
+.dioxus-flow-resize-grip-top-left { left: 0; top: 0; }
+.dioxus-flow-resize-grip-top-right { left: 100%; top: 0; }
+.dioxus-flow-resize-grip-bottom-right { left: 100%; top: 100%; }
+.dioxus-flow-resize-grip-bottom-left { left: 0; top: 100%; }
+
+.dioxus-flow-resize-grip-top,
+.dioxus-flow-resize-grip-bottom {
+    left: 0;
+    width: 100%;
+    height: 6px;
+    transform: translateY(-50%);
+}
+
+.dioxus-flow-resize-grip-top { top: 0; }
+.dioxus-flow-resize-grip-bottom { top: 100%; }
+
+.dioxus-flow-resize-grip-left,
+.dioxus-flow-resize-grip-right {
+    top: 0;
+    height: 100%;
+    width: 6px;
+    transform: translateX(-50%);
+}
+
+.dioxus-flow-resize-grip-left { left: 0; }
+.dioxus-flow-resize-grip-right { left: 100%; }
+"#;
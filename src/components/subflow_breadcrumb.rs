@@ -0,0 +1,89 @@
+//! Breadcrumb trail for groups opened as their own canvas.
+
+use crate::hooks::FlowState;
+use crate::types::FlowEvent;
+use dioxus::prelude::*;
+
+/// Subflow breadcrumb component props.
+#[derive(Props, Clone, PartialEq)]
+pub struct SubflowBreadcrumbProps<T: Clone + PartialEq + 'static> {
+    /// Flow state with the opened groups.
+    pub state: Signal<FlowState<T>>,
+    /// Label of the top-level flow.
+    #[props(default = "Flow".to_string())]
+    pub root_label: String,
+    /// Callback when the user navigates back to a level (0 = the top-level flow).
+    #[props(default)]
+    pub on_navigate: Option<EventHandler<usize>>,
+}
+
+/// Breadcrumb trail shown while drilled into a group. Clicking an entry returns to it.
+#[component]
+pub fn SubflowBreadcrumb<T: Clone + Default + PartialEq + 'static>(
+    props: SubflowBreadcrumbProps<T>,
+) -> Element {
+    let mut state = props.state;
+    let on_navigate = props.on_navigate;
+
+    // Labels of the opened groups, outermost first
+    let levels: Vec<String> = {
+        let state_ref = state.read();
+        state_ref
+            .subflow_stack
+            .iter()
+            .map(|level| {
+                state_ref
+                    .get_node(&level.group_id)
+                    .and_then(|n| n.label.clone())
+                    .unwrap_or_else(|| level.group_id.clone())
+            })
+            .collect()
+    };
+    if levels.is_empty() {
+        return rsx! {};
+    }
+
+    let mut navigate = move |depth: usize| {
+        let exited = state.write().exit_subflow_to(depth);
+        if exited.is_empty() {
+            return;
+        }
+        for id in exited {
+            state.write().emit_event(FlowEvent::SubflowExit(id));
+        }
+        if let Some(handler) = &on_navigate {
+            handler.call(depth);
+        }
+    };
+
+    let current = levels.len();
+
+    rsx! {
+        nav {
+            class: "dioxus-flow-breadcrumb",
+            // Keep clicks from reaching the pane
+            onmousedown: move |evt: MouseEvent| evt.stop_propagation(),
+
+            button {
+                class: "dioxus-flow-breadcrumb-item",
+                onclick: move |_| navigate(0),
+                "{props.root_label}"
+            }
+            for (index, label) in levels.into_iter().enumerate() {
+                span { class: "dioxus-flow-breadcrumb-separator", "/" }
+                if index + 1 == current {
+                    span {
+                        class: "dioxus-flow-breadcrumb-item dioxus-flow-breadcrumb-current",
+                        "{label}"
+                    }
+                } else {
+                    button {
+                        class: "dioxus-flow-breadcrumb-item",
+                        onclick: move |_| navigate(index + 1),
+                        "{label}"
+                    }
+                }
+            }
+        }
+    }
+}
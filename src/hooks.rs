@@ -1,15 +1,35 @@
 //! State management hooks for dioxus-flow.
 
 use crate::types::{
-    ClipboardData, Connection, ConnectionValidation, DefaultEdgeOptions, Edge, EdgeId, FlowEvent,
-    Node, NodeId, PendingConnection, Position, SelectionRect, SnapGrid, Viewport,
+    ClipboardData, Connection, ConnectionValidation, ContainerRect, DefaultEdgeOptions, DetailLevel,
+    DistributeDirection, Edge, EdgeId, FitViewNodes, FitViewOptions, FlowEvent, HandleKind,
+    HandleMatch, HandlePosition, HelperLines, LabelChange, LabelTarget, Node, NodeAlignment,
+    NodeExtent, NodeId, PendingConnection, Position, ResizeConstraints, ResizeDirection,
+    ResizeParams, SelectionRect, SnapGrid, SubflowLevel, Viewport,
 };
+use crate::spatial::SpatialIndex;
+use crate::utils::{distance, get_floating_edge_params, get_helper_lines};
 use dioxus::prelude::*;
-use std::collections::{HashMap, VecDeque};
+use gloo_timers::future::TimeoutFuture;
+use std::cell::{Cell, Ref, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
 
 /// Maximum history size for undo/redo.
 const MAX_HISTORY_SIZE: usize = 100;
 
+/// Default zoom range.
+pub(crate) const DEFAULT_MIN_ZOOM: f64 = 0.1;
+pub(crate) const DEFAULT_MAX_ZOOM: f64 = 4.0;
+
+/// Interval between viewport transition steps (about one frame).
+const VIEWPORT_ANIMATION_FRAME_MS: u32 = 16;
+
+/// How far an edge path can bend past the box around its endpoints: the largest control
+/// point offset of the built-in edge paths.
+const EDGE_CURVE_MARGIN: f64 = 150.0;
+
 /// A snapshot of the flow state for undo/redo.
 #[derive(Debug, Clone)]
 pub struct FlowSnapshot<T: Clone + PartialEq + 'static> {
@@ -17,12 +37,127 @@ pub struct FlowSnapshot<T: Clone + PartialEq + 'static> {
     pub edges: Vec<Edge>,
 }
 
+/// ID lookups over the nodes and edges of a [`FlowState`], kept in step by its methods.
+#[derive(Debug, Clone, Default)]
+struct Lookups {
+    /// Index of each node in `nodes`, by ID.
+    nodes: HashMap<NodeId, usize>,
+    /// Index of each edge in `edges`, by ID.
+    edges: HashMap<EdgeId, usize>,
+    /// IDs of the edges attached to each node, as source or target.
+    node_edges: HashMap<NodeId, Vec<EdgeId>>,
+    /// IDs of the direct children of each node.
+    children: HashMap<NodeId, Vec<NodeId>>,
+    /// Length of `nodes` the lookups describe, to notice nodes pushed or removed directly.
+    node_count: usize,
+    /// Length of `edges` the lookups describe, to notice edges pushed or removed directly.
+    edge_count: usize,
+}
+
+impl Lookups {
+    /// Build the lookups from scratch.
+    fn build<T>(nodes: &[Node<T>], edges: &[Edge]) -> Self {
+        let mut lookups = Self {
+            node_count: nodes.len(),
+            edge_count: edges.len(),
+            ..Self::default()
+        };
+        for (index, node) in nodes.iter().enumerate() {
+            lookups.nodes.insert(node.id.clone(), index);
+            if let Some(parent) = &node.parent_id {
+                lookups.children.entry(parent.clone()).or_default().push(node.id.clone());
+            }
+        }
+        for (index, edge) in edges.iter().enumerate() {
+            lookups.edges.insert(edge.id.clone(), index);
+            lookups.attach_edge(edge);
+        }
+        lookups
+    }
+
+    /// Record an edge as attached to its source and target.
+    fn attach_edge(&mut self, edge: &Edge) {
+        self.node_edges
+            .entry(edge.source.clone())
+            .or_default()
+            .push(edge.id.clone());
+        if edge.target != edge.source {
+            self.node_edges
+                .entry(edge.target.clone())
+                .or_default()
+                .push(edge.id.clone());
+        }
+    }
+}
+
+/// Nodes and edges changed since `Flow` last re-rendered them.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Changes {
+    /// Any node or edge may have changed.
+    pub(crate) all: bool,
+    /// Nodes or edges were added or removed, or the stacking order of nodes changed.
+    pub(crate) order: bool,
+    /// IDs of the changed nodes.
+    pub(crate) nodes: HashSet<NodeId>,
+    /// IDs of the changed edges.
+    pub(crate) edges: HashSet<EdgeId>,
+    /// The same changes, kept until the edge bounds index is brought in line.
+    bounds: BoundsChanges,
+}
+
+/// Nodes and edges changed since the edge bounds index was last updated.
+#[derive(Debug, Clone, Default)]
+struct BoundsChanges {
+    all: bool,
+    nodes: HashSet<NodeId>,
+    edges: HashSet<EdgeId>,
+}
+
+impl Changes {
+    /// Record that a node changed.
+    fn node(&mut self, id: &str) {
+        if !self.all {
+            self.nodes.insert(id.to_string());
+        }
+        if !self.bounds.all {
+            self.bounds.nodes.insert(id.to_string());
+        }
+    }
+
+    /// Record that an edge changed.
+    fn edge(&mut self, id: &str) {
+        if !self.all {
+            self.edges.insert(id.to_string());
+        }
+        if !self.bounds.all {
+            self.bounds.edges.insert(id.to_string());
+        }
+    }
+
+    /// Record that any node or edge may have changed.
+    fn everything(&mut self) {
+        *self = Self {
+            all: true,
+            order: true,
+            bounds: BoundsChanges {
+                all: true,
+                ..BoundsChanges::default()
+            },
+            ..Self::default()
+        };
+    }
+}
+
 /// Flow state containing all nodes, edges, and viewport information.
 #[derive(Debug, Clone)]
 pub struct FlowState<T: Clone + PartialEq + 'static = ()> {
-    /// All nodes in the flow.
+    /// All nodes in the flow. Change them through the methods below, such as
+    /// `get_node_mut`, or through `nodes_mut`, which keeps everything derived from
+    /// them in line.
     pub nodes: Vec<Node<T>>,
-    /// All edges in the flow.
+    /// All edges in the flow. Change them through the methods below, such as
+    /// `get_edge_mut`, or through `edges_mut`, which keeps everything derived from
+    /// them in line.
     pub edges: Vec<Edge>,
     /// Current viewport.
     pub viewport: Viewport,
@@ -32,7 +167,8 @@ pub struct FlowState<T: Clone + PartialEq + 'static = ()> {
     pub selected_edges: Vec<EdgeId>,
     /// Active connection being created.
     pub connection: Option<Connection>,
-    /// Node dimensions cache.
+    /// Measured node sizes by ID, mirroring [`Node::measured`]. Geometry uses the node's
+    /// own `measured`; see [`Node::dimensions`].
     pub node_dimensions: HashMap<NodeId, (f64, f64)>,
     /// Snap grid configuration.
     pub snap_grid: SnapGrid,
@@ -50,6 +186,31 @@ pub struct FlowState<T: Clone + PartialEq + 'static = ()> {
     pub connection_valid: bool,
     /// Event queue for centralized event handling.
     pub events: VecDeque<FlowEvent>,
+    /// Groups opened as their own canvas, outermost first. The last one is shown.
+    pub subflow_stack: Vec<SubflowLevel>,
+    /// Node or edge whose label is being edited in place.
+    pub editing_label: Option<LabelTarget>,
+    /// Position and size of the flow container, kept up to date by `Flow`.
+    pub container: ContainerRect,
+    /// Area of the flow, in flow coordinates, that the viewport can't be panned past.
+    pub translate_extent: Option<NodeExtent>,
+    /// Area all nodes are kept in, unless they have their own extent.
+    pub node_extent: Option<NodeExtent>,
+    /// Minimum zoom level, respected by every zoom operation.
+    pub min_zoom: f64,
+    /// Maximum zoom level, respected by every zoom operation.
+    pub max_zoom: f64,
+    /// ID lookups, edges attached to each node and children of each node.
+    lookups: RefCell<Lookups>,
+    /// Nodes and edges changed since they were last rendered.
+    changes: RefCell<Changes>,
+    /// Node bounds indexed by area. Every operation that adds, moves, resizes or removes
+    /// nodes updates the entries of the nodes it touched; see `update_absolute_positions`
+    /// for changes made directly.
+    pub spatial_index: SpatialIndex,
+    /// Edge bounds, including the curve of the path, indexed by area. Brought in line
+    /// with the recorded changes before each query.
+    edge_bounds: RefCell<SpatialIndex>,
 }
 
 impl<T: Clone + Default + PartialEq + 'static> Default for FlowState<T> {
@@ -58,6 +219,61 @@ impl<T: Clone + Default + PartialEq + 'static> Default for FlowState<T> {
     }
 }
 
+/// Direct mutable access to the nodes of a [`FlowState`], from [`FlowState::nodes_mut`].
+///
+/// When dropped, the lookups, absolute positions and spatial index are brought in line
+/// and every node and edge is re-rendered.
+pub struct NodesMut<'a, T: Clone + Default + PartialEq + 'static> {
+    state: &'a mut FlowState<T>,
+}
+
+impl<T: Clone + Default + PartialEq + 'static> Deref for NodesMut<'_, T> {
+    type Target = Vec<Node<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.state.nodes
+    }
+}
+
+impl<T: Clone + Default + PartialEq + 'static> DerefMut for NodesMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.state.nodes
+    }
+}
+
+impl<T: Clone + Default + PartialEq + 'static> Drop for NodesMut<'_, T> {
+    fn drop(&mut self) {
+        self.state.update_absolute_positions();
+    }
+}
+
+/// Direct mutable access to the edges of a [`FlowState`], from [`FlowState::edges_mut`].
+///
+/// When dropped, the lookups are rebuilt and every node and edge is re-rendered.
+pub struct EdgesMut<'a, T: Clone + Default + PartialEq + 'static> {
+    state: &'a mut FlowState<T>,
+}
+
+impl<T: Clone + Default + PartialEq + 'static> Deref for EdgesMut<'_, T> {
+    type Target = Vec<Edge>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.state.edges
+    }
+}
+
+impl<T: Clone + Default + PartialEq + 'static> DerefMut for EdgesMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.state.edges
+    }
+}
+
+impl<T: Clone + Default + PartialEq + 'static> Drop for EdgesMut<'_, T> {
+    fn drop(&mut self) {
+        self.state.rebuild_lookups();
+    }
+}
+
 impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
     /// Create a new empty flow state.
     pub fn new() -> Self {
@@ -77,13 +293,24 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
             max_z_index: 0,
             connection_valid: true,
             events: VecDeque::new(),
+            subflow_stack: Vec::new(),
+            editing_label: None,
+            container: ContainerRect::default(),
+            translate_extent: None,
+            node_extent: None,
+            min_zoom: DEFAULT_MIN_ZOOM,
+            max_zoom: DEFAULT_MAX_ZOOM,
+            lookups: RefCell::default(),
+            changes: RefCell::default(),
+            spatial_index: SpatialIndex::new(),
+            edge_bounds: RefCell::default(),
         }
     }
 
     /// Create flow state with initial nodes and edges.
     pub fn with_nodes_and_edges(nodes: Vec<Node<T>>, edges: Vec<Edge>) -> Self {
         let max_z = nodes.iter().map(|n| n.z_index).max().unwrap_or(0);
-        Self {
+        let mut state = Self {
             nodes,
             edges,
             viewport: Viewport::default(),
@@ -99,15 +326,40 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
             max_z_index: max_z,
             connection_valid: true,
             events: VecDeque::new(),
+            subflow_stack: Vec::new(),
+            editing_label: None,
+            container: ContainerRect::default(),
+            translate_extent: None,
+            node_extent: None,
+            min_zoom: DEFAULT_MIN_ZOOM,
+            max_zoom: DEFAULT_MAX_ZOOM,
+            lookups: RefCell::default(),
+            changes: RefCell::default(),
+            spatial_index: SpatialIndex::new(),
+            edge_bounds: RefCell::default(),
+        };
+        state.update_absolute_positions();
+        state
+    }
+
+    /// Take a snapshot of the current nodes and edges.
+    pub fn snapshot(&self) -> FlowSnapshot<T> {
+        FlowSnapshot {
+            nodes: self.nodes.clone(),
+            edges: self.edges.clone(),
         }
     }
 
     /// Save current state to undo history.
     pub fn save_to_history(&mut self) {
-        let snapshot = FlowSnapshot {
-            nodes: self.nodes.clone(),
-            edges: self.edges.clone(),
-        };
+        let snapshot = self.snapshot();
+        self.push_history(snapshot);
+    }
+
+    /// Push a previously taken snapshot to undo history.
+    ///
+    /// Useful for gestures that should record the state from before they started.
+    pub fn push_history(&mut self, snapshot: FlowSnapshot<T>) {
         self.undo_stack.push(snapshot);
         if self.undo_stack.len() > MAX_HISTORY_SIZE {
             self.undo_stack.remove(0);
@@ -129,6 +381,10 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
             // Restore previous state
             self.nodes = snapshot.nodes;
             self.edges = snapshot.edges;
+            self.rebuild_lookups();
+            self.refresh_spatial_index();
+            self.refresh_node_dimensions();
+            self.prune_subflow_stack();
             self.clear_selection();
             true
         } else {
@@ -149,6 +405,10 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
             // Restore next state
             self.nodes = snapshot.nodes;
             self.edges = snapshot.edges;
+            self.rebuild_lookups();
+            self.refresh_spatial_index();
+            self.refresh_node_dimensions();
+            self.prune_subflow_stack();
             self.clear_selection();
             true
         } else {
@@ -166,24 +426,148 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
         !self.redo_stack.is_empty()
     }
 
+    /// Rebuild the ID lookups, the edges attached to each node and the children of each
+    /// node from `nodes` and `edges`.
+    ///
+    /// Nodes or edges pushed or removed directly are noticed by their count, and
+    /// reordering is noticed on the next lookup that lands on the wrong element. Call
+    /// this yourself after changing nodes or edges directly in any other way, so that
+    /// `Flow` re-renders them.
+    pub fn rebuild_lookups(&mut self) {
+        *self.lookups.get_mut() = Lookups::build(&self.nodes, &self.edges);
+        self.changes.get_mut().everything();
+    }
+
+    /// Rebuild the lookups after nodes or edges were changed directly, re-rendering
+    /// everything since anything may have changed.
+    fn rebuild_stale_lookups(&self) {
+        self.lookups.replace(Lookups::build(&self.nodes, &self.edges));
+        self.changes.borrow_mut().everything();
+    }
+
+    /// Rebuild the lookups if nodes or edges were pushed or removed directly since they
+    /// were last updated.
+    fn refresh_stale_lookups(&self) {
+        let stale = {
+            let lookups = self.lookups.borrow();
+            lookups.node_count != self.nodes.len() || lookups.edge_count != self.edges.len()
+        };
+        if stale {
+            self.rebuild_stale_lookups();
+        }
+    }
+
+    /// Get the up-to-date lookups. Don't hold on to them across other lookups.
+    fn lookups(&self) -> Ref<'_, Lookups> {
+        self.refresh_stale_lookups();
+        self.lookups.borrow()
+    }
+
+    /// Get the up-to-date lookups, for methods that keep them in step.
+    fn lookups_mut(&mut self) -> &mut Lookups {
+        self.refresh_stale_lookups();
+        self.lookups.get_mut()
+    }
+
+    /// Find the index of a node in `nodes`. The lookups are rebuilt once if they point
+    /// at another node, after `nodes` was reordered directly.
+    fn node_index(&self, id: &str) -> Option<usize> {
+        let index = self.lookups().nodes.get(id).copied()?;
+        if self.nodes.get(index).is_some_and(|n| n.id == id) {
+            return Some(index);
+        }
+        self.rebuild_stale_lookups();
+        self.lookups().nodes.get(id).copied()
+    }
+
+    /// Find the index of an edge in `edges`. The lookups are rebuilt once if they point
+    /// at another edge, after `edges` was reordered directly.
+    fn edge_index(&self, id: &str) -> Option<usize> {
+        let index = self.lookups().edges.get(id).copied()?;
+        if self.edges.get(index).is_some_and(|e| e.id == id) {
+            return Some(index);
+        }
+        self.rebuild_stale_lookups();
+        self.lookups().edges.get(id).copied()
+    }
+
+    /// Change the nodes directly, in any way. Once the returned guard is dropped, the
+    /// lookups, absolute positions and spatial index are updated and everything is
+    /// re-rendered.
+    ///
+    /// ```rust,ignore
+    /// for node in state.write().nodes_mut().iter_mut() {
+    ///     node.position.x += 10.0;
+    /// }
+    /// ```
+    pub fn nodes_mut(&mut self) -> NodesMut<'_, T> {
+        NodesMut { state: self }
+    }
+
+    /// Change the edges directly, in any way. Once the returned guard is dropped, the
+    /// lookups are rebuilt and everything is re-rendered.
+    pub fn edges_mut(&mut self) -> EdgesMut<'_, T> {
+        EdgesMut { state: self }
+    }
+
     /// Get a node by ID.
     pub fn get_node(&self, id: &str) -> Option<&Node<T>> {
-        self.nodes.iter().find(|n| n.id == id)
+        self.nodes.get(self.node_index(id)?)
     }
 
-    /// Get a mutable node by ID.
+    /// Get a mutable node by ID. The node is re-rendered.
     pub fn get_node_mut(&mut self, id: &str) -> Option<&mut Node<T>> {
-        self.nodes.iter_mut().find(|n| n.id == id)
+        let index = self.node_index(id)?;
+        self.changes.get_mut().node(id);
+        self.nodes.get_mut(index)
     }
 
     /// Get an edge by ID.
     pub fn get_edge(&self, id: &str) -> Option<&Edge> {
-        self.edges.iter().find(|e| e.id == id)
+        self.edges.get(self.edge_index(id)?)
     }
 
-    /// Get a mutable edge by ID.
+    /// Get a mutable edge by ID. The edge is re-rendered.
     pub fn get_edge_mut(&mut self, id: &str) -> Option<&mut Edge> {
-        self.edges.iter_mut().find(|e| e.id == id)
+        let index = self.edge_index(id)?;
+        self.changes.get_mut().edge(id);
+        self.edges.get_mut(index)
+    }
+
+    /// Take the changes recorded since the last call, adding the edges attached to
+    /// changed nodes and the collapsed groups drawing changed nodes.
+    pub(crate) fn take_changes(&self) -> Changes {
+        self.refresh_stale_lookups();
+        let mut changes = self.changes.take();
+        // The edge bounds index takes the same changes separately
+        self.changes.borrow_mut().bounds = std::mem::take(&mut changes.bounds);
+        if changes.all {
+            return changes;
+        }
+        {
+            let lookups = self.lookups();
+            for id in &changes.nodes {
+                changes.edges.extend(lookups.node_edges.get(id).into_iter().flatten().cloned());
+            }
+        }
+        // Proxy handles on collapsed groups depend on the edges re-routed to them
+        let groups: Vec<NodeId> = changes
+            .nodes
+            .iter()
+            .filter_map(|id| self.visible_node_id(id).filter(|group| group != id))
+            .collect();
+        changes.nodes.extend(groups);
+        changes
+    }
+
+    /// Get the edges attached to a node, as source or target.
+    pub fn connected_edges(&self, id: &str) -> Vec<&Edge> {
+        let edge_ids = self.lookups().node_edges.get(id).cloned().unwrap_or_default();
+        edge_ids
+            .iter()
+            .filter_map(|edge_id| self.get_edge(edge_id))
+            .filter(|e| e.source == id || e.target == id)
+            .collect()
     }
 
     /// Add a node to the flow.
@@ -195,143 +579,1112 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
         } else {
             self.max_z_index = self.max_z_index.max(node.z_index);
         }
+        let index = self.nodes.len();
+        let lookups = self.lookups_mut();
+        lookups.nodes.insert(node.id.clone(), index);
+        if let Some(parent) = &node.parent_id {
+            lookups.children.entry(parent.clone()).or_default().push(node.id.clone());
+        }
+        lookups.node_count += 1;
+        let changes = self.changes.get_mut();
+        changes.node(&node.id);
+        changes.order = true;
+        let id = node.id.clone();
         self.nodes.push(node);
+        // Children added before their parent are positioned now
+        self.update_subtree_positions(&id);
+    }
+
+    /// Get the direct children of a node.
+    pub fn children(&self, id: &str) -> Vec<&Node<T>> {
+        let child_ids = self.lookups().children.get(id).cloned().unwrap_or_default();
+        child_ids
+            .iter()
+            .filter_map(|child| self.get_node(child))
+            .filter(|n| n.parent_id.as_deref() == Some(id))
+            .collect()
+    }
+
+    /// Get the IDs of all descendants of a node, parents before their children.
+    pub fn descendants(&self, id: &str) -> Vec<NodeId> {
+        let lookups = self.lookups();
+        // Guards against parent cycles
+        let mut seen: HashSet<&str> = HashSet::from([id]);
+        let mut descendants: Vec<&NodeId> = Vec::new();
+        let mut index = 0;
+        let mut current = id;
+        loop {
+            for child in lookups.children.get(current).into_iter().flatten() {
+                if seen.insert(child) {
+                    descendants.push(child);
+                }
+            }
+            match descendants.get(index) {
+                Some(next) => current = next,
+                None => break,
+            }
+            index += 1;
+        }
+        descendants.into_iter().cloned().collect()
+    }
+
+    /// Check whether `ancestor` is a parent, grandparent, etc. of the node `id`.
+    pub fn is_ancestor(&self, ancestor: &str, id: &str) -> bool {
+        let mut current = self.get_node(id).and_then(|n| n.parent_id.clone());
+        // Bounded by the node count in case of a parent cycle
+        for _ in 0..self.nodes.len() {
+            match current {
+                Some(parent) if parent == ancestor => return true,
+                Some(parent) => current = self.get_node(&parent).and_then(|n| n.parent_id.clone()),
+                None => return false,
+            }
+        }
+        false
+    }
+
+    /// Rebuild the lookups, recompute the absolute positions of all child nodes from
+    /// their parents and bring the spatial index in line.
+    ///
+    /// Operations that move or reparent nodes only update the nodes they touched; call
+    /// this yourself after changing `nodes` directly rather than through `nodes_mut`.
+    pub fn update_absolute_positions(&mut self) {
+        self.rebuild_lookups();
+        let relative: HashMap<NodeId, (Position, Option<NodeId>)> = self
+            .nodes
+            .iter()
+            .map(|n| (n.id.clone(), (n.position, n.parent_id.clone())))
+            .collect();
+
+        for node in &mut self.nodes {
+            let mut position = node.position;
+            let mut parent = node.parent_id.clone();
+            // Bounded by the node count in case of a parent cycle
+            for _ in 0..relative.len() {
+                let Some((parent_position, next)) = parent.and_then(|id| relative.get(&id)) else {
+                    break;
+                };
+                position.x += parent_position.x;
+                position.y += parent_position.y;
+                parent = next.clone();
+            }
+            node.position_absolute = position;
+        }
+        self.refresh_spatial_index();
+        self.refresh_node_dimensions();
+    }
+
+    /// Refill `node_dimensions` from the measured sizes of the nodes.
+    fn refresh_node_dimensions(&mut self) {
+        self.node_dimensions = self
+            .nodes
+            .iter()
+            .filter_map(|n| Some((n.id.clone(), n.measured?)))
+            .collect();
+    }
+
+    /// Recompute the absolute positions of a node and its descendants from the node's
+    /// parent, and update their spatial index entries.
+    fn update_subtree_positions(&mut self, id: &str) {
+        let mut ids = vec![id.to_string()];
+        ids.extend(self.descendants(id));
+        // Parents come before their children, so their positions are already updated
+        for id in ids {
+            let Some(index) = self.node_index(&id) else {
+                continue;
+            };
+            let parent = self.nodes[index].parent_id.as_ref().and_then(|p| self.get_node(p));
+            let origin = parent.map_or(Position::new(0.0, 0.0), |p| p.absolute_position());
+            let node = &mut self.nodes[index];
+            node.position_absolute =
+                Position::new(origin.x + node.position.x, origin.y + node.position.y);
+            let rect = node.rect();
+            self.changes.get_mut().node(&id);
+            self.spatial_index.insert(id, rect);
+        }
+    }
+
+    /// Bring the spatial index in line with the current node bounds. Only nodes whose
+    /// bounds changed are re-inserted; call it yourself after changing `nodes` directly.
+    pub fn refresh_spatial_index(&mut self) {
+        for node in &self.nodes {
+            let rect = node.rect();
+            if self.spatial_index.rect(&node.id) != Some(&rect) {
+                self.spatial_index.insert(node.id.clone(), rect);
+            }
+        }
+        if self.spatial_index.len() != self.nodes.len() {
+            let current: HashSet<&NodeId> = self.nodes.iter().map(|n| &n.id).collect();
+            let stale: Vec<NodeId> = self
+                .spatial_index
+                .ids()
+                .filter(|id| !current.contains(id))
+                .cloned()
+                .collect();
+            for id in stale {
+                self.spatial_index.remove(&id);
+            }
+        }
+    }
+
+    /// Get the IDs of the nodes whose bounds intersect `rect`, hidden ones included,
+    /// using the spatial index.
+    pub fn nodes_in_rect(&self, rect: &SelectionRect) -> Vec<NodeId> {
+        self.spatial_index.query(rect)
+    }
+
+    /// Get the shown nodes within `radius` flow pixels of a point, nearest first.
+    /// Nodes containing the point have distance 0.
+    pub fn nodes_near_point(&self, point: Position, radius: f64) -> Vec<NodeId> {
+        let area = SelectionRect {
+            x: point.x,
+            y: point.y,
+            width: 0.0,
+            height: 0.0,
+        }
+        .expanded(radius);
+        let mut nodes: Vec<(NodeId, f64)> = self
+            .nodes_in_rect(&area)
+            .into_iter()
+            .filter(|id| self.is_node_visible(id))
+            .filter_map(|id| {
+                let distance = self.spatial_index.rect(&id)?.distance_to(point.x, point.y);
+                (distance <= radius).then_some((id, distance))
+            })
+            .collect();
+        nodes.sort_by(|a, b| a.1.total_cmp(&b.1));
+        nodes.into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// Find the handle of a shown node closest to a point, within `radius` flow pixels.
+    /// With `kind`, only handles of that kind are considered.
+    pub fn nearest_handle(
+        &self,
+        point: Position,
+        radius: f64,
+        kind: Option<HandleKind>,
+    ) -> Option<HandleMatch> {
+        self.nodes_near_point(point, radius)
+            .iter()
+            .filter_map(|id| self.get_node(id))
+            .flat_map(|node| {
+                let (width, height) = node.dimensions();
+                let origin = node.absolute_position();
+                node.handles
+                    .iter()
+                    .filter(move |handle| kind.is_none_or(|kind| handle.kind == kind))
+                    .map(move |handle| {
+                        let position = handle.absolute_position(origin, width, height);
+                        HandleMatch {
+                            node_id: node.id.clone(),
+                            handle_id: handle.id.clone(),
+                            kind: handle.kind,
+                            position,
+                            distance: distance(point, position),
+                        }
+                    })
+            })
+            .filter(|handle| handle.distance <= radius)
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+
+    /// Collapse or expand a node. Collapsing hides and deselects its descendants.
+    pub fn set_node_collapsed(&mut self, id: &str, collapsed: bool) {
+        let Some(node) = self.get_node_mut(id) else {
+            return;
+        };
+        node.collapsed = collapsed;
+        // Collapsed nodes shrink to the default size
+        let rect = node.rect();
+        self.spatial_index.insert(id.to_string(), rect);
+        // Edges of the descendants are re-routed and nodes are hidden or shown
+        self.changes.get_mut().everything();
+        if collapsed {
+            let hidden = self.descendants(id);
+            for node in &mut self.nodes {
+                if hidden.contains(&node.id) {
+                    node.selected = false;
+                }
+            }
+            self.selected_nodes.retain(|n| !hidden.contains(n));
+        }
+    }
+
+    /// Toggle whether a node is collapsed. Returns the new state.
+    pub fn toggle_node_collapsed(&mut self, id: &str) -> Option<bool> {
+        let collapsed = !self.get_node(id)?.collapsed;
+        self.set_node_collapsed(id, collapsed);
+        Some(collapsed)
+    }
+
+    /// Get the group currently opened as its own canvas, if any.
+    pub fn subflow_root(&self) -> Option<&NodeId> {
+        self.subflow_stack.last().map(|level| &level.group_id)
+    }
+
+    /// Open a visible group as its own canvas. Only its descendants are shown until
+    /// it is exited.
+    pub fn enter_subflow(&mut self, id: &str) -> bool {
+        if !self.is_node_visible(id) {
+            return false;
+        }
+        let Some(group) = self.get_node(id) else {
+            return false;
+        };
+        let origin = group.absolute_position();
+
+        self.subflow_stack.push(SubflowLevel {
+            group_id: id.to_string(),
+            viewport: self.viewport,
+        });
+        self.changes.get_mut().everything();
+        // Start with the group's top-left corner near the top-left of the canvas
+        let zoom = self.viewport.zoom;
+        self.set_viewport(Viewport::new(50.0 - origin.x * zoom, 50.0 - origin.y * zoom, zoom));
+        self.clear_selection();
+        true
+    }
+
+    /// Close the innermost opened group, restoring the previous viewport.
+    pub fn exit_subflow(&mut self) -> Option<NodeId> {
+        let level = self.subflow_stack.pop()?;
+        self.changes.get_mut().everything();
+        self.set_viewport(level.viewport);
+        self.clear_selection();
+        Some(level.group_id)
+    }
+
+    /// Close opened groups until `depth` remain (0 = the top-level flow).
+    /// Returns the closed groups, innermost first.
+    pub fn exit_subflow_to(&mut self, depth: usize) -> Vec<NodeId> {
+        let mut exited = Vec::new();
+        while self.subflow_stack.len() > depth {
+            match self.exit_subflow() {
+                Some(id) => exited.push(id),
+                None => break,
+            }
+        }
+        exited
+    }
+
+    /// Drop opened groups that no longer exist, along with any opened inside them.
+    fn prune_subflow_stack(&mut self) {
+        if let Some(missing) = self
+            .subflow_stack
+            .iter()
+            .position(|level| self.get_node(&level.group_id).is_none())
+        {
+            self.set_viewport(self.subflow_stack[missing].viewport);
+            self.subflow_stack.truncate(missing);
+            self.changes.get_mut().everything();
+        }
+    }
+
+    /// Get the node an element is drawn at: the node itself, or the outermost collapsed
+    /// ancestor hiding it. Returns `None` for nodes outside the opened group.
+    pub fn visible_node_id(&self, id: &str) -> Option<NodeId> {
+        let root = self.subflow_root();
+        let mut visible = id.to_string();
+        let mut current = self.get_node(id)?;
+        // Bounded by the node count in case of a parent cycle
+        for _ in 0..self.nodes.len() {
+            let Some(parent_id) = current.parent_id.as_ref() else {
+                return root.is_none().then_some(visible);
+            };
+            if root == Some(parent_id) {
+                return Some(visible);
+            }
+            current = self.get_node(parent_id)?;
+            if current.collapsed {
+                visible = current.id.clone();
+            }
+        }
+        None
+    }
+
+    /// Check whether a node is shown: inside the opened group and not hidden by a
+    /// collapsed ancestor.
+    pub fn is_node_visible(&self, id: &str) -> bool {
+        self.visible_node_id(id).as_deref() == Some(id)
+    }
+
+    /// Get the rendered endpoints of an edge as
+    /// (source_position, source_direction, target_position, target_direction).
+    ///
+    /// Endpoints inside collapsed groups are re-routed to proxy handles on the group.
+    /// Returns `None` for edges that are hidden entirely.
+    pub fn edge_endpoints(
+        &self,
+        edge: &Edge,
+    ) -> Option<(Position, HandlePosition, Position, HandlePosition)> {
+        let source_id = self.visible_node_id(&edge.source)?;
+        let target_id = self.visible_node_id(&edge.target)?;
+        // Edges between nodes hidden in the same collapsed group disappear with them
+        if source_id == target_id && edge.source != edge.target {
+            return None;
+        }
+        let source = self.get_node(&source_id)?;
+        let target = self.get_node(&target_id)?;
+
+        if edge.floating {
+            // Floating edges attach to the node boundaries facing each other
+            return Some(get_floating_edge_params(source, target));
+        }
+
+        let (source_pos, source_dir) = if source_id == edge.source {
+            // Try to get position and direction from handle ID first, fall back to handle position
+            edge.source_handle_id
+                .as_ref()
+                .and_then(|id| source.handle_info_by_id(id))
+                .unwrap_or_else(|| (source.handle_position(edge.source_handle), edge.source_handle))
+        } else {
+            let direction = self.edge_source_direction(edge);
+            (source.handle_position(direction), direction)
+        };
+        let (target_pos, target_dir) = if target_id == edge.target {
+            edge.target_handle_id
+                .as_ref()
+                .and_then(|id| target.handle_info_by_id(id))
+                .unwrap_or_else(|| (target.handle_position(edge.target_handle), edge.target_handle))
+        } else {
+            let direction = self.edge_target_direction(edge);
+            (target.handle_position(direction), direction)
+        };
+        Some((source_pos, source_dir, target_pos, target_dir))
+    }
+
+    /// Get the proxy handles of a collapsed node as (side, is_source), one for each
+    /// side that re-routed edges attach to.
+    pub fn proxy_handles(&self, id: &str) -> Vec<(HandlePosition, bool)> {
+        let mut handles: Vec<(HandlePosition, bool)> = Vec::new();
+        if !self.get_node(id).is_some_and(|n| n.collapsed) {
+            return handles;
+        }
+        for edge in &self.edges {
+            if edge.floating || self.edge_endpoints(edge).is_none() {
+                continue;
+            }
+            if edge.source != id && self.visible_node_id(&edge.source).as_deref() == Some(id) {
+                let handle = (self.edge_source_direction(edge), true);
+                if !handles.contains(&handle) {
+                    handles.push(handle);
+                }
+            }
+            if edge.target != id && self.visible_node_id(&edge.target).as_deref() == Some(id) {
+                let handle = (self.edge_target_direction(edge), false);
+                if !handles.contains(&handle) {
+                    handles.push(handle);
+                }
+            }
+        }
+        handles
+    }
+
+    /// Get the side of its source node an edge leaves from.
+    fn edge_source_direction(&self, edge: &Edge) -> HandlePosition {
+        edge.source_handle_id
+            .as_ref()
+            .and_then(|id| self.get_node(&edge.source)?.get_handle(id))
+            .map(|handle| handle.position)
+            .unwrap_or(edge.source_handle)
+    }
+
+    /// Get the side of its target node an edge arrives at.
+    fn edge_target_direction(&self, edge: &Edge) -> HandlePosition {
+        edge.target_handle_id
+            .as_ref()
+            .and_then(|id| self.get_node(&edge.target)?.get_handle(id))
+            .map(|handle| handle.position)
+            .unwrap_or(edge.target_handle)
+    }
+
+    /// Find the group a node would be dropped into: the topmost group under the
+    /// node's center, excluding the node itself and its descendants.
+    pub fn find_drop_target(&self, id: &str) -> Option<NodeId> {
+        let center = self.get_node(id)?.center();
+        let point = SelectionRect {
+            x: center.x,
+            y: center.y,
+            width: 0.0,
+            height: 0.0,
+        };
+        let candidates: Vec<&Node<T>> = self
+            .nodes_in_rect(&point)
+            .iter()
+            .filter_map(|candidate| self.get_node(candidate))
+            .filter(|n| n.is_group() && !n.collapsed && n.id != id)
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        let descendants: HashSet<NodeId> = self.descendants(id).into_iter().collect();
+        candidates
+            .into_iter()
+            .filter(|n| !descendants.contains(&n.id) && self.is_node_visible(&n.id))
+            // Topmost as rendered: highest z-index, later nodes on ties
+            .max_by_key(|n| (self.effective_z_index(n), self.node_index(&n.id)))
+            .map(|n| n.id.clone())
+    }
+
+    /// Move a node under a new parent, or to the top level with `None`, keeping its
+    /// absolute position. The new parent is expanded to fit the node.
+    ///
+    /// Returns false if nothing changed or the new parent is invalid.
+    pub fn reparent_node(&mut self, id: &str, parent_id: Option<NodeId>) -> bool {
+        let Some(node) = self.get_node(id) else {
+            return false;
+        };
+        if node.parent_id == parent_id {
+            return false;
+        }
+        let old_parent = node.parent_id.clone();
+        let absolute = node.absolute_position();
+
+        let origin = match &parent_id {
+            Some(parent) => {
+                // A node can't become its own ancestor
+                if parent == id || self.is_ancestor(id, parent) {
+                    return false;
+                }
+                match self.get_node(parent) {
+                    Some(parent) => parent.absolute_position(),
+                    None => return false,
+                }
+            }
+            None => Position::new(0.0, 0.0),
+        };
+
+        if let Some(node) = self.get_node_mut(id) {
+            node.parent_id = parent_id.clone();
+            node.position = Position::new(absolute.x - origin.x, absolute.y - origin.y);
+        }
+        let lookups = self.lookups_mut();
+        if let Some(children) = old_parent.and_then(|old| lookups.children.get_mut(&old)) {
+            children.retain(|child| child != id);
+        }
+        if let Some(parent) = &parent_id {
+            lookups.children.entry(parent.clone()).or_default().push(id.to_string());
+        }
+        // Children are drawn above their parents
+        self.changes.get_mut().order = true;
+        self.update_subtree_positions(id);
+        if let Some(parent) = parent_id {
+            self.expand_parent(&parent, id);
+        }
+        true
+    }
+
+    /// Grow a parent node so that the given child fits inside it, then grow each further
+    /// ancestor so that the grown group fits inside its own parent.
+    ///
+    /// Children sticking out to the left or top move the parent instead, shifting
+    /// its other children back so they keep their absolute positions.
+    pub fn expand_parent(&mut self, parent_id: &str, child_id: &str) {
+        let mut parent_id = parent_id.to_string();
+        let mut child_id = child_id.to_string();
+        let mut outermost_grown = None;
+        // Bounded by the number of nodes in case of a parent cycle
+        for _ in 0..self.nodes.len() {
+            if self.grow_to_fit(&parent_id, &child_id) {
+                outermost_grown = Some(parent_id.clone());
+            }
+            let Some(next) = self.get_node(&parent_id).and_then(|p| p.parent_id.clone()) else {
+                break;
+            };
+            child_id = std::mem::replace(&mut parent_id, next);
+        }
+        if let Some(id) = outermost_grown {
+            self.update_subtree_positions(&id);
+        }
+    }
+
+    /// Grow a parent node so that the given child fits inside it, without updating
+    /// absolute positions. Returns false if it already fits.
+    fn grow_to_fit(&mut self, parent_id: &str, child_id: &str) -> bool {
+        let (Some(parent), Some(child)) = (self.get_node(parent_id), self.get_node(child_id))
+        else {
+            return false;
+        };
+        let (parent_w, parent_h) = parent.dimensions();
+        let (child_w, child_h) = child.dimensions();
+        let child_pos = child.position;
+
+        let shift_x = (-child_pos.x).max(0.0);
+        let shift_y = (-child_pos.y).max(0.0);
+        let width = (parent_w + shift_x).max(child_pos.x + shift_x + child_w);
+        let height = (parent_h + shift_y).max(child_pos.y + shift_y + child_h);
+        if width == parent_w && height == parent_h {
+            return false;
+        }
+
+        if let Some(parent) = self.get_node_mut(parent_id) {
+            parent.position =
+                Position::new(parent.position.x - shift_x, parent.position.y - shift_y);
+            parent.width = Some(width);
+            parent.height = Some(height);
+            parent.measured = Some((width, height));
+            self.node_dimensions.insert(parent_id.to_string(), (width, height));
+        }
+        if shift_x > 0.0 || shift_y > 0.0 {
+            let child_ids = self.lookups().children.get(parent_id).cloned().unwrap_or_default();
+            for child_id in child_ids {
+                if let Some(child) = self.get_node_mut(&child_id) {
+                    child.position =
+                        Position::new(child.position.x + shift_x, child.position.y + shift_y);
+                }
+            }
+        }
+        true
+    }
+
+    /// Area a node must stay within, in absolute coordinates: its own extent or the
+    /// global node extent, narrowed to its parent's bounds with `extent_parent`.
+    fn node_bounds(&self, id: &str) -> Option<NodeExtent> {
+        let node = self.get_node(id)?;
+        let absolute = node.absolute_position();
+        let offset = Position::new(absolute.x - node.position.x, absolute.y - node.position.y);
+        let shifted = |extent: NodeExtent| {
+            NodeExtent::new(
+                extent.min_x + offset.x,
+                extent.min_y + offset.y,
+                extent.max_x + offset.x,
+                extent.max_y + offset.y,
+            )
+        };
+        let mut bounds = node.extent.map(shifted).or(self.node_extent);
+        if node.extent_parent {
+            let parent = node.parent_id.as_ref().and_then(|p| self.get_node(p));
+            if let Some(parent) = parent {
+                let (parent_w, parent_h) = parent.dimensions();
+                let parent_bounds = shifted(NodeExtent::parent(parent_w, parent_h));
+                bounds = Some(match bounds {
+                    Some(b) => NodeExtent::new(
+                        b.min_x.max(parent_bounds.min_x),
+                        b.min_y.max(parent_bounds.min_y),
+                        b.max_x.min(parent_bounds.max_x),
+                        b.max_y.min(parent_bounds.max_y),
+                    ),
+                    None => parent_bounds,
+                });
+            }
+        }
+        bounds
+    }
+
+    /// Clamp a proposed (parent-relative) position of a node to its extent and,
+    /// with `extent_parent`, to the measured bounds of its parent.
+    fn constrain_position(&self, id: &str, position: Position) -> Position {
+        let Some(node) = self.get_node(id) else {
+            return position;
+        };
+        let (w, h) = node.dimensions();
+        let mut position = position;
+        if let Some(extent) = node.extent {
+            position = extent.clamp(position, w, h);
+        } else if let Some(extent) = self.node_extent {
+            // The global extent is in absolute coordinates
+            let absolute = node.absolute_position();
+            let offset = Position::new(absolute.x - node.position.x, absolute.y - node.position.y);
+            let clamped = extent.clamp(
+                Position::new(position.x + offset.x, position.y + offset.y),
+                w,
+                h,
+            );
+            position = Position::new(clamped.x - offset.x, clamped.y - offset.y);
+        }
+        if node.extent_parent {
+            let parent = node.parent_id.as_ref().and_then(|p| self.get_node(p));
+            if let Some(parent) = parent {
+                let (parent_w, parent_h) = parent.dimensions();
+                position = NodeExtent::parent(parent_w, parent_h).clamp(position, w, h);
+            }
+        }
+        position
+    }
+
+    /// Record the measured rendered size of a node in [`Node::measured`].
+    ///
+    /// Ignored while the node is collapsed, so it keeps its expanded size.
+    pub fn set_node_dimensions(&mut self, id: &str, width: f64, height: f64) {
+        if let Some(node) = self.get_node_mut(id).filter(|n| !n.collapsed) {
+            node.measured = Some((width, height));
+            let rect = node.rect();
+            self.node_dimensions.insert(id.to_string(), (width, height));
+            self.spatial_index.insert(id.to_string(), rect);
+        }
     }
 
-    /// Remove a node and all connected edges.
-    pub fn remove_node(&mut self, id: &str) {
-        self.nodes.retain(|n| n.id != id);
-        self.edges.retain(|e| e.source != id && e.target != id);
-        self.selected_nodes.retain(|n| n != id);
-    }
+    /// Remove a node, its descendants and all connected edges.
+    pub fn remove_node(&mut self, id: &str) {
+        let mut removed: HashSet<NodeId> = self.descendants(id).into_iter().collect();
+        removed.insert(id.to_string());
+        self.remove_elements(&removed, HashSet::new());
+    }
+
+    /// Remove nodes and edges by ID, along with the edges attached to removed nodes.
+    /// Only the lookup entries of removed elements and of elements that moved up are
+    /// updated. Returns the IDs of the removed edges.
+    fn remove_elements(
+        &mut self,
+        nodes: &HashSet<NodeId>,
+        mut edges: HashSet<EdgeId>,
+    ) -> HashSet<EdgeId> {
+        self.refresh_stale_lookups();
+        let lookups = self.lookups.get_mut();
+        let changes = self.changes.get_mut();
+        changes.order = true;
+
+        let mut first_node = self.nodes.len();
+        for id in nodes {
+            edges.extend(lookups.node_edges.remove(id).into_iter().flatten());
+            lookups.children.remove(id);
+            self.spatial_index.remove(id);
+            self.node_dimensions.remove(id);
+            let Some(index) = lookups.nodes.remove(id) else {
+                continue;
+            };
+            first_node = first_node.min(index);
+            let parent = self.nodes[index].parent_id.as_ref();
+            if let Some(children) = parent.and_then(|p| lookups.children.get_mut(p)) {
+                children.retain(|child| child != id);
+            }
+        }
+        if first_node < self.nodes.len() {
+            self.nodes.retain(|n| !nodes.contains(&n.id));
+            // Later nodes moved up
+            for (index, node) in self.nodes.iter().enumerate().skip(first_node) {
+                lookups.nodes.insert(node.id.clone(), index);
+            }
+            lookups.node_count = self.nodes.len();
+        }
+
+        let mut first_edge = self.edges.len();
+        let mut endpoints: HashSet<&NodeId> = HashSet::new();
+        for id in &edges {
+            let Some(index) = lookups.edges.remove(id) else {
+                continue;
+            };
+            first_edge = first_edge.min(index);
+            let edge = &self.edges[index];
+            let kept = [&edge.source, &edge.target].into_iter().filter(|n| !nodes.contains(*n));
+            endpoints.extend(kept);
+        }
+        for endpoint in endpoints {
+            if let Some(attached) = lookups.node_edges.get_mut(endpoint) {
+                attached.retain(|e| !edges.contains(e));
+            }
+            // Proxy handles of a collapsed group showing it may go away
+            changes.node(endpoint);
+        }
+        for id in &edges {
+            changes.edge(id);
+        }
+        if first_edge < self.edges.len() {
+            self.edges.retain(|e| !edges.contains(&e.id));
+            // Later edges moved up
+            for (index, edge) in self.edges.iter().enumerate().skip(first_edge) {
+                lookups.edges.insert(edge.id.clone(), index);
+            }
+            lookups.edge_count = self.edges.len();
+        }
+
+        self.selected_nodes.retain(|n| !nodes.contains(n));
+        self.selected_edges.retain(|e| !edges.contains(e));
+        self.prune_subflow_stack();
+        edges
+    }
+
+    /// Add an edge to the flow.
+    pub fn add_edge(&mut self, edge: Edge) {
+        // Check if edge already exists
+        let exists = self.connected_edges(&edge.source).iter().any(|e| {
+            e.source == edge.source
+                && e.target == edge.target
+                && e.source_handle == edge.source_handle
+                && e.target_handle == edge.target_handle
+        });
+        if !exists {
+            let index = self.edges.len();
+            let lookups = self.lookups_mut();
+            lookups.edges.insert(edge.id.clone(), index);
+            lookups.attach_edge(&edge);
+            lookups.edge_count += 1;
+            let changes = self.changes.get_mut();
+            changes.edge(&edge.id);
+            changes.order = true;
+            // Collapsed groups showing the endpoints may need a proxy handle
+            changes.node(&edge.source);
+            changes.node(&edge.target);
+            self.edges.push(edge);
+        }
+    }
+
+    /// Remove an edge by ID.
+    pub fn remove_edge(&mut self, id: &str) {
+        self.remove_elements(&HashSet::new(), HashSet::from([id.to_string()]));
+    }
+
+    /// Delete all selected nodes (with their descendants) and edges.
+    pub fn delete_selected(&mut self) -> (Vec<NodeId>, Vec<EdgeId>) {
+        let mut deleted_nodes: Vec<NodeId> = self
+            .selected_nodes
+            .iter()
+            .filter(|id| {
+                self.get_node(id)
+                    .map(|n| n.deletable)
+                    .unwrap_or(false)
+            })
+            .cloned()
+            .collect();
+        let mut node_set: HashSet<NodeId> = deleted_nodes.iter().cloned().collect();
+        for id in deleted_nodes.clone() {
+            for descendant in self.descendants(&id) {
+                if node_set.insert(descendant.clone()) {
+                    deleted_nodes.push(descendant);
+                }
+            }
+        }
+
+        let deleted_edges: HashSet<EdgeId> = self
+            .selected_edges
+            .iter()
+            .filter(|id| {
+                self.get_edge(id)
+                    .map(|e| e.deletable)
+                    .unwrap_or(false)
+            })
+            .cloned()
+            .collect();
+
+        // Edges connected to deleted nodes are deleted too
+        let deleted_edges = self.remove_elements(&node_set, deleted_edges);
+        self.clear_selection();
+
+        let mut all_deleted_edges: Vec<EdgeId> = deleted_edges.into_iter().collect();
+        all_deleted_edges.sort();
+
+        (deleted_nodes, all_deleted_edges)
+    }
+
+    /// Update a node's position with optional snap-to-grid.
+    ///
+    /// For child nodes the position is relative to the parent.
+    pub fn update_node_position(&mut self, id: &str, position: Position) {
+        let new_pos = if self.snap_grid.enabled {
+            self.snap_grid.snap(position)
+        } else {
+            position
+        };
+
+        // Apply extent constraints if set
+        let new_pos = self.constrain_position(id, new_pos);
+
+        if let Some(node) = self.get_node_mut(id) {
+            node.position = new_pos;
+        }
+        self.update_subtree_positions(id);
+    }
+
+    /// Resize a node by dragging one of its resize grips by (dx, dy) flow pixels
+    /// from the `start` position and size, applying snap-to-grid, constraints and the
+    /// node's extent.
+    pub fn resize_node(
+        &mut self,
+        id: &str,
+        direction: ResizeDirection,
+        start: ResizeParams,
+        dx: f64,
+        dy: f64,
+        constraints: &ResizeConstraints,
+    ) -> Option<ResizeParams> {
+        // Resize in absolute coordinates, so that the edges snap to the same grid as
+        // top-level nodes
+        let node = self.get_node(id)?;
+        let absolute = node.absolute_position();
+        let offset = Position::new(absolute.x - node.position.x, absolute.y - node.position.y);
+        let start_absolute = ResizeParams {
+            position: Position::new(start.position.x + offset.x, start.position.y + offset.y),
+            ..start
+        };
+
+        // Keep the moving sides inside the node's extent
+        let mut constraints = *constraints;
+        if let Some(bounds) = self.node_bounds(id) {
+            let left = start_absolute.position.x;
+            let top = start_absolute.position.y;
+            let max_width = if direction.moves_left() {
+                left + start_absolute.width - bounds.min_x
+            } else {
+                bounds.max_x - left
+            };
+            let max_height = if direction.moves_top() {
+                top + start_absolute.height - bounds.min_y
+            } else {
+                bounds.max_y - top
+            };
+            constraints.max_width = constraints.max_width.min(max_width);
+            constraints.max_height = constraints.max_height.min(max_height);
+        }
+
+        let resized = start_absolute.resized(direction, dx, dy, &constraints, &self.snap_grid);
+        let params = ResizeParams {
+            position: Position::new(resized.position.x - offset.x, resized.position.y - offset.y),
+            ..resized
+        };
+        let node = self.get_node_mut(id)?;
+        node.position = params.position;
+        node.width = Some(params.width);
+        node.height = Some(params.height);
+        // Keep the measured size in step until the next measurement arrives
+        node.measured = Some((params.width, params.height));
+        self.node_dimensions.insert(id.to_string(), (params.width, params.height));
+        self.update_subtree_positions(id);
+        Some(params)
+    }
+
+    /// Move selected nodes by a delta.
+    ///
+    /// Children of selected nodes already move with their parent, so they are skipped.
+    pub fn move_selected_nodes(&mut self, dx: f64, dy: f64) {
+        let selected = self.selected_nodes.clone();
+        let snap_enabled = self.snap_grid.enabled;
+        let snap_grid = self.snap_grid;
+
+        for id in &selected {
+            if selected.iter().any(|other| self.is_ancestor(other, id)) {
+                continue;
+            }
+            let Some(node) = self.get_node(id) else {
+                continue;
+            };
+            if !node.draggable {
+                continue;
+            }
+
+            let new_pos = Position::new(node.position.x + dx, node.position.y + dy);
+            let final_pos = if snap_enabled {
+                snap_grid.snap(new_pos)
+            } else {
+                new_pos
+            };
+
+            // Apply extent constraints
+            let final_pos = self.constrain_position(id, final_pos);
 
-    /// Add an edge to the flow.
-    pub fn add_edge(&mut self, edge: Edge) {
-        // Check if edge already exists
-        let exists = self.edges.iter().any(|e| {
-            e.source == edge.source
-                && e.target == edge.target
-                && e.source_handle == edge.source_handle
-                && e.target_handle == edge.target_handle
-        });
-        if !exists {
-            self.edges.push(edge);
+            if let Some(node) = self.get_node_mut(id) {
+                node.position = final_pos;
+            }
+            self.update_subtree_positions(id);
         }
     }
 
-    /// Remove an edge by ID.
-    pub fn remove_edge(&mut self, id: &str) {
-        self.edges.retain(|e| e.id != id);
-        self.selected_edges.retain(|e| e != id);
-    }
-
-    /// Delete all selected nodes and edges.
-    pub fn delete_selected(&mut self) -> (Vec<NodeId>, Vec<EdgeId>) {
-        let deleted_nodes: Vec<NodeId> = self
-            .selected_nodes
-            .iter()
-            .filter(|id| {
-                self.get_node(id)
-                    .map(|n| n.deletable)
-                    .unwrap_or(false)
+    /// Align the selected nodes to an edge or centre of their bounding box, using their
+    /// measured sizes. Recorded as one undo step. Returns the moved nodes with their new
+    /// positions.
+    pub fn align_selected_nodes(&mut self, alignment: NodeAlignment) -> Vec<(NodeId, Position)> {
+        let rects = self.arrangeable_nodes();
+        if rects.len() < 2 {
+            return Vec::new();
+        }
+        let min_x = rects.iter().map(|r| r.1).fold(f64::INFINITY, f64::min);
+        let min_y = rects.iter().map(|r| r.2).fold(f64::INFINITY, f64::min);
+        let max_x = rects.iter().map(|r| r.1 + r.3).fold(f64::NEG_INFINITY, f64::max);
+        let max_y = rects.iter().map(|r| r.2 + r.4).fold(f64::NEG_INFINITY, f64::max);
+
+        let targets = rects
+            .into_iter()
+            .map(|(id, x, y, width, height)| {
+                let target = match alignment {
+                    NodeAlignment::Left => Position::new(min_x, y),
+                    NodeAlignment::Center => Position::new((min_x + max_x - width) / 2.0, y),
+                    NodeAlignment::Right => Position::new(max_x - width, y),
+                    NodeAlignment::Top => Position::new(x, min_y),
+                    NodeAlignment::Middle => Position::new(x, (min_y + max_y - height) / 2.0),
+                    NodeAlignment::Bottom => Position::new(x, max_y - height),
+                };
+                (id, target)
             })
-            .cloned()
             .collect();
+        self.arrange_nodes(targets)
+    }
 
-        let deleted_edges: Vec<EdgeId> = self
-            .selected_edges
+    /// Space the selected nodes evenly between the outermost two, using their measured
+    /// sizes. Needs at least three movable nodes. Recorded as one undo step. Returns the
+    /// moved nodes with their new positions.
+    pub fn distribute_selected_nodes(
+        &mut self,
+        direction: DistributeDirection,
+    ) -> Vec<(NodeId, Position)> {
+        let mut rects = self.arrangeable_nodes();
+        if rects.len() < 3 {
+            return Vec::new();
+        }
+        let horizontal = direction == DistributeDirection::Horizontal;
+        // Position and size along the distribution axis
+        let span = |r: &(NodeId, f64, f64, f64, f64)| {
+            if horizontal {
+                (r.1, r.3)
+            } else {
+                (r.2, r.4)
+            }
+        };
+        rects.sort_by(|a, b| span(a).0.total_cmp(&span(b).0));
+
+        let (first_start, _) = span(&rects[0]);
+        let end = rects
             .iter()
-            .filter(|id| {
-                self.get_edge(id)
-                    .map(|e| e.deletable)
-                    .unwrap_or(false)
+            .map(|r| span(r).0 + span(r).1)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let total_size: f64 = rects.iter().map(|r| span(r).1).sum();
+        let gap = (end - first_start - total_size) / (rects.len() - 1) as f64;
+
+        let mut cursor = first_start;
+        let targets = rects
+            .into_iter()
+            .map(|r| {
+                let (_, size) = span(&r);
+                let target = if horizontal {
+                    Position::new(cursor, r.2)
+                } else {
+                    Position::new(r.1, cursor)
+                };
+                cursor += size + gap;
+                (r.0, target)
             })
-            .cloned()
             .collect();
+        self.arrange_nodes(targets)
+    }
 
-        // Also delete edges connected to deleted nodes
-        let edges_to_delete: Vec<EdgeId> = self
-            .edges
+    /// Selected nodes that can be aligned or distributed, as (id, x, y, width, height) in
+    /// absolute coordinates. Children of selected nodes follow their parent and are left out.
+    fn arrangeable_nodes(&self) -> Vec<(NodeId, f64, f64, f64, f64)> {
+        self.selected_nodes
             .iter()
-            .filter(|e| deleted_nodes.contains(&e.source) || deleted_nodes.contains(&e.target))
-            .map(|e| e.id.clone())
-            .collect();
+            .filter(|id| !self.selected_nodes.iter().any(|other| self.is_ancestor(other, id)))
+            .filter_map(|id| self.get_node(id))
+            .filter(|node| node.draggable && self.is_node_visible(&node.id))
+            .map(|node| {
+                let pos = node.absolute_position();
+                let (width, height) = node.dimensions();
+                (node.id.clone(), pos.x, pos.y, width, height)
+            })
+            .collect()
+    }
 
-        for id in &deleted_nodes {
-            self.remove_node(id);
+    /// Move nodes to absolute target positions, within their extents, as one undo step.
+    /// Returns the nodes that actually moved with their new (parent-relative) positions.
+    fn arrange_nodes(&mut self, targets: Vec<(NodeId, Position)>) -> Vec<(NodeId, Position)> {
+        let snapshot = self.snapshot();
+        let mut moved = Vec::new();
+        for (id, target) in targets {
+            let Some(node) = self.get_node(&id) else {
+                continue;
+            };
+            let current = node.absolute_position();
+            let relative = Position::new(
+                node.position.x + target.x - current.x,
+                node.position.y + target.y - current.y,
+            );
+            let relative = self.constrain_position(&id, relative);
+            if let Some(node) = self.get_node_mut(&id) {
+                if node.position != relative {
+                    node.position = relative;
+                    moved.push((id, relative));
+                }
+            }
         }
-
-        for id in &deleted_edges {
-            self.remove_edge(id);
+        for (id, _) in &moved {
+            self.update_subtree_positions(id);
         }
-
-        for id in &edges_to_delete {
-            self.remove_edge(id);
+        if !moved.is_empty() {
+            self.push_history(snapshot);
         }
+        moved
+    }
 
-        self.clear_selection();
-
-        let mut all_deleted_edges = deleted_edges;
-        all_deleted_edges.extend(edges_to_delete);
-        all_deleted_edges.sort();
-        all_deleted_edges.dedup();
+    /// Get the nodes moved by dragging `id`, with their current positions: the whole
+    /// selection if the node is selected, otherwise just the node. Children of moved
+    /// nodes are left out since they follow their parent.
+    pub fn drag_start_positions(&self, id: &str) -> Vec<(NodeId, Position)> {
+        let Some(node) = self.get_node(id) else {
+            return Vec::new();
+        };
+        if !node.selected {
+            return vec![(node.id.clone(), node.position)];
+        }
 
-        (deleted_nodes, all_deleted_edges)
+        self.selected_nodes
+            .iter()
+            .filter(|id| !self.selected_nodes.iter().any(|other| self.is_ancestor(other, id)))
+            .filter_map(|id| self.get_node(id))
+            .filter(|n| n.draggable)
+            .map(|n| (n.id.clone(), n.position))
+            .collect()
     }
 
-    /// Update a node's position with optional snap-to-grid.
-    pub fn update_node_position(&mut self, id: &str, position: Position) {
-        // Read snap_grid values before borrowing node mutably
+    /// Move dragged nodes by (dx, dy) flow pixels from their start positions,
+    /// applying snap-to-grid and extent constraints to each node.
+    /// Returns the new positions.
+    pub fn drag_nodes(
+        &mut self,
+        starts: &[(NodeId, Position)],
+        dx: f64,
+        dy: f64,
+    ) -> Vec<(NodeId, Position)> {
         let snap_enabled = self.snap_grid.enabled;
-        let snapped_pos = self.snap_grid.snap(position);
+        let snap_grid = self.snap_grid;
 
-        if let Some(node) = self.get_node_mut(id) {
-            let mut new_pos = if snap_enabled {
-                snapped_pos
-            } else {
-                position
-            };
+        let moved: Vec<(NodeId, Position)> = starts
+            .iter()
+            .map(|(id, start)| {
+                let new_pos = Position::new(start.x + dx, start.y + dy);
+                let new_pos = if snap_enabled {
+                    snap_grid.snap(new_pos)
+                } else {
+                    new_pos
+                };
+                (id.clone(), self.constrain_position(id, new_pos))
+            })
+            .collect();
 
-            // Apply extent constraints if set
-            if let Some(extent) = node.extent {
-                let w = node.width.unwrap_or(150.0);
-                let h = node.height.unwrap_or(40.0);
-                new_pos = extent.clamp(new_pos, w, h);
+        for (id, position) in &moved {
+            if let Some(node) = self.get_node_mut(id) {
+                node.position = *position;
             }
-
-            node.position = new_pos;
+            self.update_subtree_positions(id);
         }
+        moved
     }
 
-    /// Move selected nodes by a delta.
-    pub fn move_selected_nodes(&mut self, dx: f64, dy: f64) {
-        let selected = self.selected_nodes.clone();
-        let snap_enabled = self.snap_grid.enabled;
-        let snap_grid = self.snap_grid.clone();
-
-        for id in selected {
-            if let Some(node) = self.get_node_mut(&id) {
-                if node.draggable {
-                    let new_pos = Position::new(node.position.x + dx, node.position.y + dy);
-                    let final_pos = if snap_enabled {
-                        snap_grid.snap(new_pos)
-                    } else {
-                        new_pos
-                    };
-
-                    // Apply extent constraints
-                    let final_pos = if let Some(extent) = node.extent {
-                        let w = node.width.unwrap_or(150.0);
-                        let h = node.height.unwrap_or(40.0);
-                        extent.clamp(final_pos, w, h)
-                    } else {
-                        final_pos
-                    };
-
-                    node.position = final_pos;
-                }
-            }
+    /// Find alignment guides for a dragged node against the other shown nodes in the
+    /// visible area, and the shift (in flow coordinates) that snaps it onto them. The
+    /// dragged nodes and their descendants are not used as guides.
+    pub fn helper_lines(
+        &self,
+        id: &str,
+        dragged: &[NodeId],
+        threshold: f64,
+    ) -> (HelperLines, Position) {
+        let Some(node) = self.get_node(id) else {
+            return (HelperLines::default(), Position::new(0.0, 0.0));
+        };
+        let mut excluded: Vec<NodeId> = dragged.to_vec();
+        for dragged_id in dragged {
+            excluded.extend(self.descendants(dragged_id));
         }
+
+        let rect = |n: &Node<T>| {
+            let pos = n.absolute_position();
+            let (width, height) = n.dimensions();
+            (pos.x, pos.y, width, height)
+        };
+        let others: Vec<(f64, f64, f64, f64)> = self
+            .nodes_in_rect(&self.get_visible_rect())
+            .iter()
+            .filter(|id| !excluded.contains(id) && self.is_node_visible(id))
+            .filter_map(|id| self.get_node(id))
+            .map(rect)
+            .collect();
+        get_helper_lines(rect(node), &others, threshold)
     }
 
     /// Bring a node to front (increase z-index).
@@ -341,6 +1694,7 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
         if let Some(node) = self.get_node_mut(id) {
             node.z_index = new_z;
         }
+        self.changes.get_mut().order = true;
     }
 
     /// Send a node to back (decrease z-index).
@@ -354,6 +1708,7 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
         if let Some(node) = self.get_node_mut(id) {
             node.z_index = 0;
         }
+        self.changes.get_mut().everything();
     }
 
     /// Select a node.
@@ -391,7 +1746,7 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
         if !self.selected_edges.contains(&id.to_string()) {
             self.selected_edges.push(id.to_string());
         }
-        if let Some(edge) = self.edges.iter_mut().find(|e| e.id == id) {
+        if let Some(edge) = self.get_edge_mut(id) {
             edge.selected = true;
         }
     }
@@ -420,10 +1775,10 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
         }
 
         let node_ids: Vec<String> = self
-            .nodes
-            .iter()
-            .filter(|n| n.selectable && rect.intersects_node(n))
-            .map(|n| n.id.clone())
+            .nodes_in_rect(&rect)
+            .into_iter()
+            .filter(|id| self.get_node(id).is_some_and(|n| n.selectable))
+            .filter(|id| self.is_node_visible(id))
             .collect();
 
         for id in node_ids {
@@ -436,18 +1791,25 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
         }
     }
 
-    /// Select all nodes and edges.
+    /// Select all visible nodes and edges.
     pub fn select_all(&mut self) {
-        for node in &mut self.nodes {
-            if node.selectable {
+        self.changes.get_mut().everything();
+        let visible: Vec<bool> = self.nodes.iter().map(|n| self.is_node_visible(&n.id)).collect();
+        let visible_edges: Vec<bool> = self
+            .edges
+            .iter()
+            .map(|e| self.edge_endpoints(e).is_some())
+            .collect();
+        for (node, visible) in self.nodes.iter_mut().zip(visible) {
+            if node.selectable && visible {
                 node.selected = true;
                 if !self.selected_nodes.contains(&node.id) {
                     self.selected_nodes.push(node.id.clone());
                 }
             }
         }
-        for edge in &mut self.edges {
-            if edge.selectable {
+        for (edge, visible) in self.edges.iter_mut().zip(visible_edges) {
+            if edge.selectable && visible {
                 edge.selected = true;
                 if !self.selected_edges.contains(&edge.id) {
                     self.selected_edges.push(edge.id.clone());
@@ -458,19 +1820,29 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
 
     /// Clear all selections.
     pub fn clear_selection(&mut self) {
-        for node in &mut self.nodes {
+        let changes = self.changes.get_mut();
+        for node in self.nodes.iter_mut().filter(|n| n.selected) {
             node.selected = false;
+            changes.node(&node.id);
         }
-        for edge in &mut self.edges {
+        for edge in self.edges.iter_mut().filter(|e| e.selected) {
             edge.selected = false;
+            changes.edge(&edge.id);
         }
         self.selected_nodes.clear();
         self.selected_edges.clear();
     }
 
-    /// Copy selected nodes and edges to clipboard.
+    /// Copy selected nodes (with their descendants) and edges to clipboard.
     pub fn copy_selected(&mut self) {
-        let selected_node_ids: Vec<String> = self.selected_nodes.clone();
+        let mut selected_node_ids: Vec<String> = self.selected_nodes.clone();
+        for id in self.selected_nodes.iter() {
+            for descendant in self.descendants(id) {
+                if !selected_node_ids.contains(&descendant) {
+                    selected_node_ids.push(descendant);
+                }
+            }
+        }
 
         // Copy selected nodes
         let nodes: Vec<Node<T>> = self
@@ -516,17 +1888,27 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
         // Clear selection before pasting
         self.clear_selection();
 
+        for node in &clipboard_nodes {
+            let new_id = format!("{}-copy-{}", node.id, uuid::Uuid::new_v4());
+            id_map.insert(node.id.clone(), new_id);
+        }
+
         // Paste nodes with new IDs and offset positions
         for node in clipboard_nodes {
-            let new_id = format!("{}-copy-{}", node.id, uuid::Uuid::new_v4());
-            id_map.insert(node.id.clone(), new_id.clone());
+            let new_id = id_map[&node.id].clone();
 
             let mut new_node = node.clone();
             new_node.id = new_id.clone();
-            new_node.position = Position::new(
-                node.position.x + offset.x,
-                node.position.y + offset.y,
-            );
+            match node.parent_id.as_ref().and_then(|p| id_map.get(p)) {
+                // Copied children stay in place relative to their copied parent
+                Some(new_parent) => new_node.parent_id = Some(new_parent.clone()),
+                None => {
+                    new_node.position = Position::new(
+                        node.position.x + offset.x,
+                        node.position.y + offset.y,
+                    );
+                }
+            }
             new_node.selected = true;
 
             self.add_node(new_node);
@@ -556,36 +1938,174 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
         !self.clipboard.nodes.is_empty()
     }
 
-    /// Update the viewport.
+    /// Update the viewport, keeping it within the translate extent.
     pub fn set_viewport(&mut self, viewport: Viewport) {
-        self.viewport = viewport;
+        self.viewport = self.constrain_viewport(viewport);
+    }
+
+    /// Restrict panning to an area of the flow (`None` for no limit).
+    pub fn set_translate_extent(&mut self, extent: Option<NodeExtent>) {
+        self.translate_extent = extent;
+        self.set_viewport(self.viewport);
+    }
+
+    /// Keep nodes without their own extent inside an area of the flow (`None` for no
+    /// limit). Applies the next time nodes are moved.
+    pub fn set_node_extent(&mut self, extent: Option<NodeExtent>) {
+        self.node_extent = extent;
+    }
+
+    /// Set the zoom range, zooming the current view into it if needed.
+    ///
+    /// Bounds that are not positive numbers fall back to the default range, and a
+    /// maximum below the minimum is raised to it.
+    pub fn set_zoom_range(&mut self, min_zoom: f64, max_zoom: f64) {
+        let valid = |zoom: f64, default: f64| {
+            if zoom > 0.0 && zoom.is_finite() {
+                zoom
+            } else {
+                default
+            }
+        };
+        let min_zoom = valid(min_zoom, DEFAULT_MIN_ZOOM);
+        self.min_zoom = min_zoom;
+        self.max_zoom = valid(max_zoom, DEFAULT_MAX_ZOOM).max(min_zoom);
+        let center = self.container.center();
+        self.set_zoom(self.viewport.zoom, center.x, center.y);
+    }
+
+    /// Clamp a zoom level to the zoom range. If the range fields were set to an inverted
+    /// range directly, the minimum wins; NaN bounds are ignored.
+    pub fn clamp_zoom(&self, zoom: f64) -> f64 {
+        zoom.min(self.max_zoom).max(self.min_zoom)
+    }
+
+    /// Bring a viewport within the zoom range, and shift it so the visible area stays
+    /// inside the translate extent. If the extent is smaller than the visible area, it is
+    /// centred instead.
+    pub fn constrain_viewport(&self, viewport: Viewport) -> Viewport {
+        let zoom = self.clamp_zoom(viewport.zoom);
+        let Some(extent) = self.translate_extent else {
+            return Viewport { zoom, ..viewport };
+        };
+        let clamp_axis = |offset: f64, min: f64, max: f64, size: f64| {
+            // Offsets that put the extent's edges at the container's edges
+            let high = -min * zoom;
+            let low = size - max * zoom;
+            if low > high {
+                (low + high) / 2.0
+            } else {
+                offset.clamp(low, high)
+            }
+        };
+        Viewport {
+            x: clamp_axis(viewport.x, extent.min_x, extent.max_x, self.container.width),
+            y: clamp_axis(viewport.y, extent.min_y, extent.max_y, self.container.height),
+            zoom,
+        }
+    }
+
+    /// Record the position and size of the flow container.
+    pub fn set_container(&mut self, container: ContainerRect) {
+        self.container = container;
+        // The visible area changed, so the translate extent may now be exceeded
+        self.set_viewport(self.viewport);
+    }
+
+    /// Convert client coordinates (e.g. from a mouse event) to flow coordinates.
+    pub fn screen_to_flow(&self, client_x: f64, client_y: f64) -> Position {
+        let local = self.container.to_local(client_x, client_y);
+        self.viewport.screen_to_flow(local.x, local.y)
+    }
+
+    /// Convert flow coordinates to client coordinates.
+    pub fn flow_to_screen(&self, flow_x: f64, flow_y: f64) -> Position {
+        let local = self.viewport.flow_to_screen(flow_x, flow_y);
+        Position::new(local.x + self.container.x, local.y + self.container.y)
+    }
+
+    /// Convert a client position (e.g. from a mouse event) to flow coordinates.
+    pub fn project(&self, position: Position) -> Position {
+        self.screen_to_flow(position.x, position.y)
+    }
+
+    /// Convert a flow position to client coordinates.
+    pub fn unproject(&self, position: Position) -> Position {
+        self.flow_to_screen(position.x, position.y)
+    }
+
+    /// Get the area of the flow currently shown in the container, in flow coordinates.
+    pub fn get_visible_rect(&self) -> SelectionRect {
+        let top_left = self.viewport.screen_to_flow(0.0, 0.0);
+        SelectionRect {
+            x: top_left.x,
+            y: top_left.y,
+            width: self.container.width / self.viewport.zoom,
+            height: self.container.height / self.viewport.zoom,
+        }
+    }
+
+    /// Centre the view on a flow position at the given zoom level.
+    pub fn set_center(&mut self, x: f64, y: f64, zoom: f64) {
+        let zoom = self.clamp_zoom(zoom);
+        let center = self.container.center();
+        self.set_viewport(Viewport {
+            x: center.x - x * zoom,
+            y: center.y - y * zoom,
+            zoom,
+        });
+    }
+
+    /// Fit the view to an area in flow coordinates, with `padding` as a ratio of its size.
+    pub fn fit_bounds(&mut self, bounds: SelectionRect, padding: f64) {
+        self.set_viewport(self.viewport_for_bounds(bounds, padding, self.min_zoom, self.max_zoom));
+    }
+
+    /// Centre the view on a node, keeping the current zoom unless `zoom` is given. Nodes
+    /// hidden in a collapsed group centre on the group. Returns false if the node doesn't
+    /// exist or is outside the opened subflow.
+    pub fn zoom_to_node(&mut self, id: &str, zoom: Option<f64>) -> bool {
+        let Some(shown_id) = self.visible_node_id(id) else {
+            return false;
+        };
+        let Some(center) = self.get_node(&shown_id).map(|n| n.center()) else {
+            return false;
+        };
+        let zoom = zoom.unwrap_or(self.viewport.zoom);
+        self.set_center(center.x, center.y, zoom);
+        true
+    }
+
+    /// Zoom in or out by `delta` around the centre of the container.
+    pub fn zoom_by(&mut self, delta: f64) {
+        let center = self.container.center();
+        self.zoom(delta, center.x, center.y);
     }
 
     /// Pan the viewport.
     pub fn pan(&mut self, dx: f64, dy: f64) {
-        self.viewport.x += dx;
-        self.viewport.y += dy;
+        let viewport = self.viewport;
+        self.set_viewport(Viewport::new(viewport.x + dx, viewport.y + dy, viewport.zoom));
     }
 
     /// Zoom the viewport around a point.
     pub fn zoom(&mut self, delta: f64, center_x: f64, center_y: f64) {
         let old_zoom = self.viewport.zoom;
-        let new_zoom = (old_zoom + delta).clamp(0.1, 4.0);
-
-        // Adjust position to zoom around the center point
-        self.viewport.x = center_x - (center_x - self.viewport.x) * new_zoom / old_zoom;
-        self.viewport.y = center_y - (center_y - self.viewport.y) * new_zoom / old_zoom;
-        self.viewport.zoom = new_zoom;
+        self.set_zoom(old_zoom + delta, center_x, center_y);
     }
 
     /// Set zoom level.
     pub fn set_zoom(&mut self, zoom: f64, center_x: f64, center_y: f64) {
         let old_zoom = self.viewport.zoom;
-        let new_zoom = zoom.clamp(0.1, 4.0);
+        let new_zoom = self.clamp_zoom(zoom);
 
-        self.viewport.x = center_x - (center_x - self.viewport.x) * new_zoom / old_zoom;
-        self.viewport.y = center_y - (center_y - self.viewport.y) * new_zoom / old_zoom;
-        self.viewport.zoom = new_zoom;
+        // Adjust position to zoom around the center point
+        let viewport = self.viewport;
+        self.set_viewport(Viewport::new(
+            center_x - (center_x - viewport.x) * new_zoom / old_zoom,
+            center_y - (center_y - viewport.y) * new_zoom / old_zoom,
+            new_zoom,
+        ));
     }
 
     /// Zoom in by a fixed amount.
@@ -598,41 +2118,127 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
         self.zoom(-0.2, center_x, center_y);
     }
 
-    /// Fit the view to show all nodes.
-    pub fn fit_view(&mut self, padding: f64, container_width: f64, container_height: f64) {
-        if self.nodes.is_empty() {
-            return;
+    /// Get the bounding box of the visible nodes picked by `nodes`, in flow coordinates.
+    pub fn nodes_bounds(&self, nodes: &FitViewNodes) -> Option<SelectionRect> {
+        let mut bounds: Option<(f64, f64, f64, f64)> = None;
+        for node in self.nodes.iter().filter(|n| self.is_node_visible(&n.id)) {
+            let included = match nodes {
+                FitViewNodes::All => true,
+                FitViewNodes::Selected => node.selected,
+                FitViewNodes::Ids(ids) => ids.contains(&node.id),
+            };
+            if !included {
+                continue;
+            }
+            let (w, h) = node.dimensions();
+            let position = node.absolute_position();
+            let (min_x, min_y, max_x, max_y) = bounds.unwrap_or((
+                f64::MAX,
+                f64::MAX,
+                f64::MIN,
+                f64::MIN,
+            ));
+            bounds = Some((
+                min_x.min(position.x),
+                min_y.min(position.y),
+                max_x.max(position.x + w),
+                max_y.max(position.y + h),
+            ));
         }
+        bounds.map(|(min_x, min_y, max_x, max_y)| SelectionRect {
+            x: min_x,
+            y: min_y,
+            width: max_x - min_x,
+            height: max_y - min_y,
+        })
+    }
 
-        let mut min_x = f64::MAX;
-        let mut min_y = f64::MAX;
-        let mut max_x = f64::MIN;
-        let mut max_y = f64::MIN;
-
-        for node in &self.nodes {
-            let (w, h) = self
-                .node_dimensions
-                .get(&node.id)
-                .copied()
-                .unwrap_or((node.width.unwrap_or(150.0), node.height.unwrap_or(40.0)));
+    /// Get the viewport that fits the nodes picked by `options` into the container, or
+    /// `None` if there are no such nodes.
+    pub fn fit_view_viewport(&self, options: &FitViewOptions) -> Option<Viewport> {
+        let bounds = self.nodes_bounds(&options.nodes)?;
+        // The options can narrow the flow's zoom range but not widen it
+        Some(self.viewport_for_bounds(
+            bounds,
+            options.padding,
+            options.min_zoom.max(self.min_zoom),
+            options.max_zoom.min(self.max_zoom),
+        ))
+    }
 
-            min_x = min_x.min(node.position.x);
-            min_y = min_y.min(node.position.y);
-            max_x = max_x.max(node.position.x + w);
-            max_y = max_y.max(node.position.y + h);
+    /// Fit the view to the nodes picked by `options`, jumping straight there (use
+    /// `animate_viewport` with `fit_view_viewport` for a transition). Returns false if
+    /// there was nothing to fit.
+    pub fn fit_view_with_options(&mut self, options: &FitViewOptions) -> bool {
+        match self.fit_view_viewport(options) {
+            Some(viewport) => {
+                self.set_viewport(viewport);
+                true
+            }
+            None => false,
         }
+    }
+
+    /// Fit the view to show all visible nodes, with `padding` flow pixels around them.
+    #[deprecated(
+        since = "0.1.0",
+        note = "use `fit_view_with_options`, which uses the measured container size"
+    )]
+    pub fn fit_view(&mut self, padding: f64, container_width: f64, container_height: f64) {
+        self.fit_view_padded(padding, container_width, container_height);
+    }
+
+    /// Fit the view to show all visible nodes in the container, with `padding` flow
+    /// pixels around them.
+    pub fn fit_view_to_container(&mut self, padding: f64) {
+        let container = self.container;
+        self.fit_view_padded(padding, container.width, container.height);
+    }
 
-        let content_width = max_x - min_x + padding * 2.0;
-        let content_height = max_y - min_y + padding * 2.0;
+    /// Fit all visible nodes into a container of the given size, with a fixed padding in
+    /// flow pixels and the zoom capped at 1.
+    fn fit_view_padded(&mut self, padding: f64, container_width: f64, container_height: f64) {
+        let Some(bounds) = self.nodes_bounds(&FitViewNodes::All) else {
+            return;
+        };
+        let content_width = bounds.width + padding * 2.0;
+        let content_height = bounds.height + padding * 2.0;
 
         let zoom_x = container_width / content_width;
         let zoom_y = container_height / content_height;
-        let zoom = zoom_x.min(zoom_y).min(1.0).max(0.1);
+        let zoom = self.clamp_zoom(zoom_x.min(zoom_y).min(1.0));
+
+        self.set_viewport(Viewport {
+            x: (container_width - content_width * zoom) / 2.0 - (bounds.x - padding) * zoom,
+            y: (container_height - content_height * zoom) / 2.0 - (bounds.y - padding) * zoom,
+            zoom,
+        });
+    }
 
-        self.viewport.zoom = zoom;
-        self.viewport.x = (container_width - content_width * zoom) / 2.0 - (min_x - padding) * zoom;
-        self.viewport.y =
-            (container_height - content_height * zoom) / 2.0 - (min_y - padding) * zoom;
+    /// Viewport showing `bounds` centred in the container, with `padding` as a ratio of
+    /// the bounds size.
+    fn viewport_for_bounds(
+        &self,
+        bounds: SelectionRect,
+        padding: f64,
+        min_zoom: f64,
+        max_zoom: f64,
+    ) -> Viewport {
+        let container = self.container;
+        let content_width = (bounds.width * (1.0 + padding * 2.0)).max(1.0);
+        let content_height = (bounds.height * (1.0 + padding * 2.0)).max(1.0);
+
+        let zoom_x = container.width / content_width;
+        let zoom_y = container.height / content_height;
+        let zoom = zoom_x.min(zoom_y).min(max_zoom).max(min_zoom);
+
+        let center_x = bounds.x + bounds.width / 2.0;
+        let center_y = bounds.y + bounds.height / 2.0;
+        Viewport {
+            x: container.width / 2.0 - center_x * zoom,
+            y: container.height / 2.0 - center_y * zoom,
+            zoom,
+        }
     }
 
     /// Set snap grid configuration.
@@ -653,7 +2259,7 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
         }
 
         // Check if connection already exists
-        let exists = self.edges.iter().any(|e| {
+        let exists = self.connected_edges(&pending.source).iter().any(|e| {
             e.source == pending.source
                 && e.target == pending.target
                 && e.source_handle == pending.source_handle
@@ -705,6 +2311,73 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
         }
     }
 
+    /// Start editing a node or edge label in place. Returns false if the element doesn't
+    /// exist.
+    pub fn start_label_edit(&mut self, target: LabelTarget) -> bool {
+        let exists = match &target {
+            LabelTarget::Node(id) => self.get_node(id).is_some(),
+            LabelTarget::Edge(id) => self.get_edge(id).is_some(),
+        };
+        if exists {
+            self.mark_label_target();
+            self.editing_label = Some(target);
+            self.mark_label_target();
+        }
+        exists
+    }
+
+    /// Stop editing the label without changing it.
+    pub fn cancel_label_edit(&mut self) {
+        self.mark_label_target();
+        self.editing_label = None;
+    }
+
+    /// Record that the node or edge whose label is being edited changed.
+    fn mark_label_target(&mut self) {
+        let changes = self.changes.get_mut();
+        match &self.editing_label {
+            Some(LabelTarget::Node(id)) => changes.node(id),
+            Some(LabelTarget::Edge(id)) => changes.edge(id),
+            None => {}
+        }
+    }
+
+    /// Finish editing the label, setting it to `text` (an empty text clears it). Records
+    /// an undo step and returns the change if the label changed.
+    pub fn commit_label_edit(&mut self, text: &str) -> Option<LabelChange> {
+        self.mark_label_target();
+        let target = self.editing_label.take()?;
+        let text = text.trim();
+        let new_label = (!text.is_empty()).then(|| text.to_string());
+
+        let old_label = match &target {
+            LabelTarget::Node(id) => self.get_node(id)?.label.clone(),
+            LabelTarget::Edge(id) => self.get_edge(id)?.label.clone(),
+        };
+        if old_label == new_label {
+            return None;
+        }
+
+        self.save_to_history();
+        match &target {
+            LabelTarget::Node(id) => {
+                if let Some(node) = self.get_node_mut(id) {
+                    node.label = new_label.clone();
+                }
+            }
+            LabelTarget::Edge(id) => {
+                if let Some(edge) = self.get_edge_mut(id) {
+                    edge.label = new_label.clone();
+                }
+            }
+        }
+        Some(LabelChange {
+            target,
+            old_label,
+            new_label,
+        })
+    }
+
     /// Cancel the current connection.
     pub fn cancel_connection(&mut self) {
         self.connection = None;
@@ -754,7 +2427,8 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
             .with_type(self.default_edge_options.edge_type)
             .with_stroke(&self.default_edge_options.stroke)
             .with_stroke_width(self.default_edge_options.stroke_width)
-            .with_animated(self.default_edge_options.animated);
+            .with_animated(self.default_edge_options.animated)
+            .with_floating(self.default_edge_options.floating);
 
             // Set handle IDs if available
             if let Some(src_handle_id) = conn.source_handle_id {
@@ -772,12 +2446,117 @@ impl<T: Clone + Default + PartialEq + 'static> FlowState<T> {
     }
 
     /// Get nodes sorted by z-index (for rendering).
+    ///
+    /// Children are always ordered above their parents.
     pub fn nodes_sorted_by_z_index(&self) -> Vec<&Node<T>> {
         let mut nodes: Vec<&Node<T>> = self.nodes.iter().collect();
-        nodes.sort_by_key(|n| n.z_index);
+        nodes.sort_by_cached_key(|n| self.effective_z_index(n));
+        nodes
+    }
+
+    /// Get the shown nodes within `margin` flow pixels of the visible area, plus the
+    /// `keep` nodes wherever they are, sorted by z-index for rendering.
+    pub fn nodes_near_visible_area(&self, margin: f64, keep: &[NodeId]) -> Vec<&Node<T>> {
+        let area = self.get_visible_rect().expanded(margin);
+        let mut ids: HashSet<NodeId> = self.nodes_in_rect(&area).into_iter().collect();
+        ids.extend(keep.iter().cloned());
+        let mut nodes: Vec<&Node<T>> = ids
+            .iter()
+            .filter(|id| self.is_node_visible(id))
+            .filter_map(|id| self.get_node(id))
+            .collect();
+        // Later nodes go on top on ties, as in `nodes_sorted_by_z_index`
+        nodes.sort_by_cached_key(|n| (self.effective_z_index(n), self.node_index(&n.id)));
         nodes
     }
 
+    /// Get the edges whose path may pass within `margin` flow pixels of the visible
+    /// area, plus selected edges and edges attached to the `keep` nodes, in `edges` order.
+    pub fn edges_near_visible_area(&self, margin: f64, keep: &[NodeId]) -> Vec<&Edge> {
+        self.refresh_edge_bounds();
+        let area = self.get_visible_rect().expanded(margin);
+        let mut edge_ids: HashSet<EdgeId> =
+            self.edge_bounds.borrow().query(&area).into_iter().collect();
+        edge_ids.extend(self.selected_edges.iter().cloned());
+        {
+            let lookups = self.lookups();
+            for id in keep {
+                edge_ids.extend(lookups.node_edges.get(id).into_iter().flatten().cloned());
+            }
+        }
+        let mut indices: Vec<usize> =
+            edge_ids.iter().filter_map(|id| self.edge_index(id)).collect();
+        indices.sort_unstable();
+        indices.into_iter().map(|index| &self.edges[index]).collect()
+    }
+
+    /// Get the bounds of the rendered path of an edge, or `None` if it is hidden.
+    fn edge_rect(&self, edge: &Edge) -> Option<SelectionRect> {
+        let (source, _, target, _) = self.edge_endpoints(edge)?;
+        let rect = SelectionRect {
+            x: source.x.min(target.x),
+            y: source.y.min(target.y),
+            width: (source.x - target.x).abs(),
+            height: (source.y - target.y).abs(),
+        };
+        Some(rect.expanded(EDGE_CURVE_MARGIN))
+    }
+
+    /// Bring the edge bounds index in line with the changes recorded since it was last
+    /// updated, re-inserting the changed edges and the edges attached to changed nodes.
+    fn refresh_edge_bounds(&self) {
+        self.refresh_stale_lookups();
+        let changes = std::mem::take(&mut self.changes.borrow_mut().bounds);
+        let mut bounds = self.edge_bounds.borrow_mut();
+        if changes.all {
+            bounds.clear();
+            for edge in &self.edges {
+                if let Some(rect) = self.edge_rect(edge) {
+                    bounds.insert(edge.id.clone(), rect);
+                }
+            }
+            return;
+        }
+
+        let mut edge_ids = changes.edges;
+        {
+            let lookups = self.lookups();
+            for id in &changes.nodes {
+                edge_ids.extend(lookups.node_edges.get(id).into_iter().flatten().cloned());
+            }
+        }
+        for id in edge_ids {
+            match self.get_edge(&id).and_then(|edge| self.edge_rect(edge)) {
+                Some(rect) => bounds.insert(id, rect),
+                None => {
+                    bounds.remove(&id);
+                }
+            }
+        }
+    }
+
+    /// Get the z-index a node is rendered with: its own, raised above its ancestors.
+    /// Returns (z_index, depth) so that children sort after parents on ties.
+    fn effective_z_index(&self, node: &Node<T>) -> (i32, usize) {
+        let mut ancestors: Vec<&Node<T>> = Vec::new();
+        let mut parent = node.parent_id.as_ref().and_then(|p| self.get_node(p));
+        while let Some(p) = parent {
+            // Guard against parent cycles
+            if ancestors.len() >= self.nodes.len() {
+                break;
+            }
+            ancestors.push(p);
+            parent = p.parent_id.as_ref().and_then(|id| self.get_node(id));
+        }
+
+        let z_index = ancestors
+            .iter()
+            .rev()
+            .chain(std::iter::once(&node))
+            .fold(i32::MIN, |z, n| n.z_index.max(z.saturating_add(1)));
+        (z_index, ancestors.len())
+    }
+
     /// Emit an event to the event queue.
     pub fn emit_event(&mut self, event: FlowEvent) {
         self.events.push_back(event);
@@ -807,6 +2586,82 @@ pub fn use_flow<T: Clone + Default + PartialEq + 'static>(
     use_signal(|| FlowState::with_nodes_and_edges(initial_nodes, initial_edges))
 }
 
+/// Detail level of the node being rendered, provided by `Flow` to node content.
+#[derive(Clone, Copy)]
+pub(crate) struct NodeDetail(pub(crate) Memo<DetailLevel>);
+
+/// Hook to get the detail level a node is drawn at, for use in components rendered
+/// from `node_render` to pick a compact variant when zoomed out. Returns
+/// `DetailLevel::Full` outside of a flow node.
+pub fn use_node_detail() -> DetailLevel {
+    try_use_context::<NodeDetail>().map_or(DetailLevel::Full, |detail| (detail.0)())
+}
+
+/// Move the viewport to `target` over `duration_ms` milliseconds with an ease-in-out
+/// curve. The transition stops early if anything else changes the viewport meanwhile,
+/// such as the user panning or another transition starting.
+///
+/// Must be called from within a component or event handler.
+pub fn animate_viewport<T: Clone + Default + PartialEq + 'static>(
+    mut state: Signal<FlowState<T>>,
+    target: Viewport,
+    duration_ms: u32,
+) {
+    let start = state.peek().viewport;
+    if duration_ms == 0 || start == target {
+        state.write().set_viewport(target);
+        return;
+    }
+    spawn(async move {
+        let mut elapsed = 0;
+        let mut current = start;
+        while elapsed < duration_ms {
+            TimeoutFuture::new(VIEWPORT_ANIMATION_FRAME_MS).await;
+            if state.peek().viewport != current {
+                return;
+            }
+            elapsed = (elapsed + VIEWPORT_ANIMATION_FRAME_MS).min(duration_ms);
+            let t = elapsed as f64 / duration_ms as f64;
+            let eased = if t < 0.5 {
+                4.0 * t * t * t
+            } else {
+                1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+            };
+            state.write().set_viewport(Viewport {
+                x: start.x + (target.x - start.x) * eased,
+                y: start.y + (target.y - start.y) * eased,
+                zoom: start.zoom + (target.zoom - start.zoom) * eased,
+            });
+            current = state.peek().viewport;
+        }
+    });
+}
+
+/// Apply zoom limits given as component props to the flow state's zoom range. Limits
+/// left at their defaults keep a range set on the state itself; once they have changed,
+/// the defaults are applied like any other limits.
+pub(crate) fn use_zoom_range_props<T: Clone + Default + PartialEq + 'static>(
+    mut state: Signal<FlowState<T>>,
+    min_zoom: f64,
+    max_zoom: f64,
+) {
+    let changed = use_hook(|| Rc::new(Cell::new(false)));
+    use_effect(use_reactive!(|min_zoom, max_zoom| {
+        let defaults = (min_zoom, max_zoom) == (DEFAULT_MIN_ZOOM, DEFAULT_MAX_ZOOM);
+        if defaults && !changed.get() {
+            return;
+        }
+        changed.set(true);
+        let current = {
+            let state_ref = state.peek();
+            (state_ref.min_zoom, state_ref.max_zoom)
+        };
+        if (min_zoom, max_zoom) != current {
+            state.write().set_zoom_range(min_zoom, max_zoom);
+        }
+    }));
+}
+
 /// Hook to handle flow events from a FlowState signal.
 ///
 /// This hook processes all pending events from the flow state and calls
@@ -840,3 +2695,79 @@ where
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> SelectionRect {
+        SelectionRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn direct_changes_take_effect() {
+        let mut state: FlowState = FlowState::with_nodes_and_edges(
+            vec![
+                Node::new("group", 0.0, 0.0).with_dimensions(200.0, 200.0),
+                Node::new("child", 10.0, 10.0).with_parent("group"),
+                Node::new("other", 500.0, 500.0),
+            ],
+            vec![Edge::new("e1", "child", "other")],
+        );
+        state.take_changes();
+
+        {
+            let mut nodes = state.nodes_mut();
+            nodes[0].position = Position::new(1000.0, 0.0);
+            nodes.push(Node::new("new", 2000.0, 2000.0));
+            nodes.swap(1, 2);
+        }
+        assert!(state.take_changes().all);
+        let child = state.get_node("child").unwrap();
+        assert_eq!(child.absolute_position(), Position::new(1010.0, 10.0));
+        assert_eq!(state.get_node("new").unwrap().id, "new");
+        let mut moved = state.nodes_in_rect(&rect(1005.0, 5.0, 10.0, 10.0));
+        moved.sort();
+        assert_eq!(moved, ["child", "group"]);
+        assert!(state.nodes_in_rect(&rect(5.0, 5.0, 10.0, 10.0)).is_empty());
+
+        {
+            let mut edges = state.edges_mut();
+            edges[0].target = "new".to_string();
+            edges.push(Edge::new("e2", "other", "new"));
+        }
+        assert!(state.take_changes().all);
+        assert!(state.connected_edges("other").iter().map(|e| &e.id).eq(["e2"]));
+        assert_eq!(state.connected_edges("new").len(), 2);
+    }
+
+    #[test]
+    fn edges_crossing_the_visible_area_are_kept() {
+        let mut state: FlowState = FlowState::with_nodes_and_edges(
+            vec![
+                Node::new("left", -5000.0, 100.0),
+                Node::new("right", 5000.0, 100.0),
+                Node::new("far-left", -5000.0, 5000.0),
+                Node::new("far-right", 5000.0, 5000.0),
+            ],
+            vec![Edge::new("across", "left", "right"), Edge::new("below", "far-left", "far-right")],
+        );
+        let visible = |state: &FlowState| -> Vec<EdgeId> {
+            state.edges_near_visible_area(0.0, &[]).iter().map(|e| e.id.clone()).collect()
+        };
+        assert_eq!(visible(&state), ["across"]);
+
+        // Moved nodes bring their edges along
+        state.update_node_position("far-left", Position::new(-5000.0, 300.0));
+        state.update_node_position("far-right", Position::new(5000.0, 300.0));
+        assert_eq!(visible(&state), ["across", "below"]);
+
+        state.remove_edge("across");
+        assert_eq!(visible(&state), ["below"]);
+    }
+}
@@ -9,26 +9,23 @@
 //! use dioxus_flow::prelude::*;
 //!
 //! fn App() -> Element {
-//!     let nodes = use_signal(|| vec![
-//!         Node::new("1", 100.0, 100.0).with_data("Node 1"),
-//!         Node::new("2", 300.0, 200.0).with_data("Node 2"),
-//!     ]);
-//!
-//!     let edges = use_signal(|| vec![
-//!         Edge::new("e1", "1", "2"),
-//!     ]);
+//!     let state = use_flow::<()>(
+//!         vec![
+//!             Node::new("1", 100.0, 100.0).with_label("Node 1"),
+//!             Node::new("2", 300.0, 200.0).with_label("Node 2"),
+//!         ],
+//!         vec![Edge::new("e1", "1", "2")],
+//!     );
 //!
 //!     rsx! {
-//!         Flow {
-//!             nodes: nodes,
-//!             edges: edges,
-//!         }
+//!         Flow { state: state }
 //!     }
 //! }
 //! ```
 
 pub mod components;
 pub mod hooks;
+pub mod spatial;
 pub mod types;
 pub mod utils;
 
@@ -41,12 +38,18 @@ pub mod prelude {
     pub use crate::components::edge::{ConnectionLine, EdgeComponent};
     pub use crate::components::flow::{Flow, FLOW_STYLES};
     pub use crate::components::handle::{Handle, HandleType};
+    pub use crate::components::helper_lines::HelperLinesRenderer;
+    pub use crate::components::label_editor::LabelEditor;
     pub use crate::components::minimap::{MiniMap, MiniMapPosition};
     pub use crate::components::node::NodeComponent;
+    pub use crate::components::node_resizer::{NodeResizer, NODE_RESIZER_STYLES};
     pub use crate::components::selection_box::{SelectionBox, SelectionBoxState, SELECTION_BOX_STYLES};
+    pub use crate::components::subflow_breadcrumb::SubflowBreadcrumb;
 
     // Hooks
-    pub use crate::hooks::{use_flow, use_flow_events, FlowState};
+    pub use crate::hooks::{
+        animate_viewport, use_flow, use_flow_events, use_node_detail, FlowState,
+    };
 
     // Types
     pub use crate::types::*;
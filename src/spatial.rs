@@ -0,0 +1,396 @@
+//! Spatial index for finding nodes by area without scanning every node.
+
+use crate::types::{NodeId, SelectionRect};
+use std::collections::HashMap;
+
+/// Entries a quadrant holds before it is split into four.
+const QUAD_CAPACITY: usize = 8;
+
+/// Maximum quadrant nesting depth.
+const MAX_QUAD_DEPTH: usize = 10;
+
+/// Half the side length of the area covered before the index first grows.
+const INITIAL_HALF_EXTENT: f64 = 2048.0;
+
+/// Quadtree of node bounds in absolute flow coordinates. `FlowState` keeps a second one
+/// of edge bounds, by edge ID.
+///
+/// Each entry is stored in the smallest quadrant that fully contains it, so entries
+/// straddling a quadrant border stay with the parent. The covered area grows as
+/// needed when an entry lies outside it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpatialIndex {
+    root: Quad,
+    rects: HashMap<NodeId, SelectionRect>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Quad {
+    bounds: SelectionRect,
+    depth: usize,
+    entries: Vec<(NodeId, SelectionRect)>,
+    children: Option<Box<[Quad; 4]>>,
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpatialIndex {
+    /// Create an empty index.
+    pub fn new() -> Self {
+        Self {
+            root: Quad::new(
+                SelectionRect {
+                    x: -INITIAL_HALF_EXTENT,
+                    y: -INITIAL_HALF_EXTENT,
+                    width: INITIAL_HALF_EXTENT * 2.0,
+                    height: INITIAL_HALF_EXTENT * 2.0,
+                },
+                0,
+            ),
+            rects: HashMap::new(),
+        }
+    }
+
+    /// Number of indexed entries.
+    pub fn len(&self) -> usize {
+        self.rects.len()
+    }
+
+    /// Check whether the index is empty.
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    /// Get the indexed bounds of an entry.
+    pub fn rect(&self, id: &str) -> Option<&SelectionRect> {
+        self.rects.get(id)
+    }
+
+    /// Iterate over the indexed IDs, in no particular order.
+    pub fn ids(&self) -> impl Iterator<Item = &NodeId> {
+        self.rects.keys()
+    }
+
+    /// Insert an entry, replacing its previous bounds if it is already indexed.
+    pub fn insert(&mut self, id: NodeId, rect: SelectionRect) {
+        self.remove(&id);
+        let finite = [rect.x, rect.y, rect.width, rect.height].iter().all(|v| v.is_finite());
+        // Non-finite bounds never fit a quadrant and stay in the root
+        if finite && !contains_rect(&self.root.bounds, &rect) {
+            self.grow_to(&rect);
+        }
+        self.root.insert(id.clone(), rect);
+        self.rects.insert(id, rect);
+    }
+
+    /// Remove an entry. Returns its bounds if it was indexed.
+    pub fn remove(&mut self, id: &str) -> Option<SelectionRect> {
+        let rect = self.rects.remove(id)?;
+        self.root.remove(id, &rect);
+        Some(rect)
+    }
+
+    /// Remove every entry.
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Get the IDs of all entries intersecting `area`, in no particular order.
+    pub fn query(&self, area: &SelectionRect) -> Vec<NodeId> {
+        let mut found = Vec::new();
+        self.root.query(area, &mut found);
+        found
+    }
+
+    /// Double the covered area until it contains `rect`, then re-insert every entry.
+    fn grow_to(&mut self, rect: &SelectionRect) {
+        let mut bounds = self.root.bounds;
+        while !contains_rect(&bounds, rect) {
+            bounds = SelectionRect {
+                x: bounds.x - bounds.width / 2.0,
+                y: bounds.y - bounds.height / 2.0,
+                width: bounds.width * 2.0,
+                height: bounds.height * 2.0,
+            };
+        }
+        self.root = Quad::new(bounds, 0);
+        for (id, rect) in &self.rects {
+            self.root.insert(id.clone(), *rect);
+        }
+    }
+}
+
+impl Quad {
+    fn new(bounds: SelectionRect, depth: usize) -> Self {
+        Self {
+            bounds,
+            depth,
+            entries: Vec::new(),
+            children: None,
+        }
+    }
+
+    /// Index of the child quadrant that fully contains `rect`, if any.
+    fn child_for(&self, rect: &SelectionRect) -> Option<usize> {
+        let mid_x = self.bounds.x + self.bounds.width / 2.0;
+        let mid_y = self.bounds.y + self.bounds.height / 2.0;
+        let column = if rect.x + rect.width < mid_x {
+            0
+        } else if rect.x > mid_x {
+            1
+        } else {
+            return None;
+        };
+        let row = if rect.y + rect.height < mid_y {
+            0
+        } else if rect.y > mid_y {
+            2
+        } else {
+            return None;
+        };
+        Some(row + column)
+    }
+
+    fn insert(&mut self, id: NodeId, rect: SelectionRect) {
+        if let Some(index) = self.child_for(&rect) {
+            if let Some(children) = &mut self.children {
+                children[index].insert(id, rect);
+                return;
+            }
+        }
+        self.entries.push((id, rect));
+        if self.children.is_none()
+            && self.entries.len() > QUAD_CAPACITY
+            && self.depth < MAX_QUAD_DEPTH
+        {
+            self.split();
+        }
+    }
+
+    /// Create the four child quadrants and move down every entry that fits in one.
+    fn split(&mut self) {
+        let half_w = self.bounds.width / 2.0;
+        let half_h = self.bounds.height / 2.0;
+        let quadrant = |column: f64, row: f64| {
+            Quad::new(
+                SelectionRect {
+                    x: self.bounds.x + column * half_w,
+                    y: self.bounds.y + row * half_h,
+                    width: half_w,
+                    height: half_h,
+                },
+                self.depth + 1,
+            )
+        };
+        let mut children = Box::new([
+            quadrant(0.0, 0.0),
+            quadrant(1.0, 0.0),
+            quadrant(0.0, 1.0),
+            quadrant(1.0, 1.0),
+        ]);
+        let mut kept = Vec::new();
+        for (id, rect) in std::mem::take(&mut self.entries) {
+            match self.child_for(&rect) {
+                Some(index) => children[index].insert(id, rect),
+                None => kept.push((id, rect)),
+            }
+        }
+        self.entries = kept;
+        self.children = Some(children);
+    }
+
+    /// Remove an entry, following the same path `insert` took for its bounds.
+    fn remove(&mut self, id: &str, rect: &SelectionRect) -> bool {
+        if let Some(index) = self.child_for(rect) {
+            if let Some(children) = &mut self.children {
+                return children[index].remove(id, rect);
+            }
+        }
+        match self.entries.iter().position(|(entry, _)| entry == id) {
+            Some(index) => {
+                self.entries.swap_remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn query(&self, area: &SelectionRect, found: &mut Vec<NodeId>) {
+        if !self.bounds.intersects(area) {
+            return;
+        }
+        found.extend(
+            self.entries
+                .iter()
+                .filter(|(_, rect)| area.intersects(rect))
+                .map(|(id, _)| id.clone()),
+        );
+        if let Some(children) = &self.children {
+            for child in children.iter() {
+                child.query(area, found);
+            }
+        }
+    }
+}
+
+/// Check whether `inner` lies entirely within `outer`.
+fn contains_rect(outer: &SelectionRect, inner: &SelectionRect) -> bool {
+    inner.x >= outer.x
+        && inner.y >= outer.y
+        && inner.x + inner.width <= outer.x + outer.width
+        && inner.y + inner.height <= outer.y + outer.height
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> SelectionRect {
+        SelectionRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn sorted(mut ids: Vec<NodeId>) -> Vec<NodeId> {
+        ids.sort();
+        ids
+    }
+
+    /// Deterministic pseudo-random numbers in [0, 1).
+    fn random(seed: &mut u64) -> f64 {
+        *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (*seed >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut index = SpatialIndex::new();
+        index.insert("a".into(), rect(0.0, 0.0, 10.0, 10.0));
+        index.insert("b".into(), rect(100.0, 100.0, 10.0, 10.0));
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.query(&rect(-5.0, -5.0, 20.0, 20.0)), vec!["a".to_string()]);
+
+        assert_eq!(index.remove("a"), Some(rect(0.0, 0.0, 10.0, 10.0)));
+        assert_eq!(index.remove("a"), None);
+        assert_eq!(index.len(), 1);
+        assert!(index.query(&rect(-5.0, -5.0, 20.0, 20.0)).is_empty());
+        assert_eq!(index.rect("b"), Some(&rect(100.0, 100.0, 10.0, 10.0)));
+    }
+
+    #[test]
+    fn insert_replaces_previous_bounds() {
+        let mut index = SpatialIndex::new();
+        for i in 0..20 {
+            index.insert(format!("n{i}"), rect(i as f64 * 50.0, 0.0, 10.0, 10.0));
+        }
+        index.insert("n0".into(), rect(500.0, 500.0, 10.0, 10.0));
+        assert_eq!(index.len(), 20);
+        assert!(index.query(&rect(-5.0, -5.0, 20.0, 20.0)).is_empty());
+        assert_eq!(index.query(&rect(495.0, 495.0, 20.0, 20.0)), vec!["n0".to_string()]);
+    }
+
+    #[test]
+    fn grows_to_fit_distant_entries() {
+        let mut index = SpatialIndex::new();
+        for i in 0..20 {
+            index.insert(format!("n{i}"), rect(i as f64 * 10.0, i as f64 * 10.0, 5.0, 5.0));
+        }
+        let far = rect(-100_000.0, 50_000.0, 10.0, 10.0);
+        index.insert("far".into(), far);
+        assert!(contains_rect(&index.root.bounds, &far));
+
+        // Entries inserted before growing are still found and removable
+        let near_n10 = rect(98.0, 98.0, 4.0, 4.0);
+        assert_eq!(index.query(&near_n10), vec!["n10".to_string()]);
+        assert_eq!(index.query(&far), vec!["far".to_string()]);
+        assert!(index.remove("n10").is_some());
+        assert!(index.query(&near_n10).is_empty());
+        assert_eq!(index.len(), 20);
+    }
+
+    #[test]
+    fn entries_straddling_a_split_line_stay_findable() {
+        let mut index = SpatialIndex::new();
+        // Crosses both of the root's midlines, so it can't move into a child
+        let middle = rect(-10.0, -10.0, 20.0, 20.0);
+        index.insert("middle".into(), middle);
+        for i in 0..(QUAD_CAPACITY * 4) {
+            let offset = 100.0 + i as f64 * 20.0;
+            index.insert(format!("n{i}"), rect(offset, offset, 10.0, 10.0));
+        }
+        assert!(index.root.children.is_some());
+        assert_eq!(index.query(&rect(5.0, 5.0, 1.0, 1.0)), vec!["middle".to_string()]);
+        assert_eq!(index.remove("middle"), Some(middle));
+        assert!(index.query(&rect(5.0, 5.0, 1.0, 1.0)).is_empty());
+    }
+
+    #[test]
+    fn non_finite_bounds_stay_in_the_root() {
+        let mut index = SpatialIndex::new();
+        let bounds = index.root.bounds;
+        index.insert("nan".into(), rect(f64::NAN, 0.0, 10.0, 10.0));
+        index.insert("inf".into(), rect(0.0, 0.0, f64::INFINITY, 10.0));
+        assert_eq!(index.root.bounds, bounds);
+        assert_eq!(index.len(), 2);
+        // Like `SelectionRect::intersects`, NaN bounds never compare as disjoint
+        assert_eq!(
+            sorted(index.query(&rect(0.0, 0.0, 10.0, 10.0))),
+            vec!["inf".to_string(), "nan".to_string()]
+        );
+        assert!(index.remove("nan").is_some());
+        assert!(index.remove("inf").is_some());
+        assert!(index.is_empty());
+    }
+
+    #[test]
+    fn query_matches_a_full_scan() {
+        let mut seed = 7;
+        let mut index = SpatialIndex::new();
+        let mut rects = HashMap::new();
+        for i in 0..500 {
+            let r = rect(
+                random(&mut seed) * 10_000.0 - 5_000.0,
+                random(&mut seed) * 10_000.0 - 5_000.0,
+                random(&mut seed) * 300.0,
+                random(&mut seed) * 300.0,
+            );
+            index.insert(format!("n{i}"), r);
+            rects.insert(format!("n{i}"), r);
+        }
+        // Move and remove some entries so the index isn't only ever appended to
+        for i in (0..500).step_by(3) {
+            let id = format!("n{i}");
+            if i % 2 == 0 {
+                index.remove(&id);
+                rects.remove(&id);
+            } else {
+                let r = rect(random(&mut seed) * 20_000.0, 0.0, 50.0, 50.0);
+                index.insert(id.clone(), r);
+                rects.insert(id, r);
+            }
+        }
+        assert_eq!(index.len(), rects.len());
+
+        for _ in 0..100 {
+            let area = rect(
+                random(&mut seed) * 24_000.0 - 7_000.0,
+                random(&mut seed) * 12_000.0 - 6_000.0,
+                random(&mut seed) * 3_000.0,
+                random(&mut seed) * 3_000.0,
+            );
+            let expected: Vec<NodeId> = rects
+                .iter()
+                .filter(|(_, r)| area.intersects(r))
+                .map(|(id, _)| id.clone())
+                .collect();
+            assert_eq!(sorted(index.query(&area)), sorted(expected));
+        }
+    }
+}
@@ -65,6 +65,53 @@ impl Viewport {
     }
 }
 
+/// Position and size of the flow container on the page, in client coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContainerRect {
+    /// Left edge in client coordinates.
+    pub x: f64,
+    /// Top edge in client coordinates.
+    pub y: f64,
+    /// Width in pixels.
+    pub width: f64,
+    /// Height in pixels.
+    pub height: f64,
+}
+
+impl Default for ContainerRect {
+    /// A typical canvas size, used until the container has been measured.
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: 800.0,
+            height: 600.0,
+        }
+    }
+}
+
+impl ContainerRect {
+    /// Create a container rect from its client position and size.
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Convert client coordinates to coordinates relative to the container.
+    pub fn to_local(&self, client_x: f64, client_y: f64) -> Position {
+        Position::new(client_x - self.x, client_y - self.y)
+    }
+
+    /// Centre of the container, relative to the container.
+    pub fn center(&self) -> Position {
+        Position::new(self.width / 2.0, self.height / 2.0)
+    }
+}
+
 /// Handle position on a node.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum HandlePosition {
@@ -217,12 +264,19 @@ pub struct Node<T = ()> {
     pub id: NodeId,
     /// Display label for the node.
     pub label: Option<String>,
-    /// Position of the node in flow coordinates.
+    /// Position of the node in flow coordinates, relative to its parent if it has one.
     pub position: Position,
+    /// Absolute position of a child node in flow coordinates, maintained by `FlowState`.
+    pub position_absolute: Position,
+    /// ID of the parent node. Child positions are relative to the parent.
+    pub parent_id: Option<NodeId>,
     /// Width of the node (optional, defaults to auto-sizing).
     pub width: Option<f64>,
     /// Height of the node (optional, defaults to auto-sizing).
     pub height: Option<f64>,
+    /// Rendered size of the node as measured by the flow, if known. Takes precedence
+    /// over `width`/`height` in all geometry; see [`Node::dimensions`].
+    pub measured: Option<(f64, f64)>,
     /// Custom data associated with the node.
     pub data: T,
     /// Whether the node is selected.
@@ -247,6 +301,12 @@ pub struct Node<T = ()> {
     pub style: HashMap<String, String>,
     /// Movement extent/bounds (min_x, min_y, max_x, max_y). None = no bounds.
     pub extent: Option<NodeExtent>,
+    /// Whether the node is confined to the bounds of its parent.
+    pub extent_parent: bool,
+    /// Whether the node is collapsed, hiding its descendants.
+    pub collapsed: bool,
+    /// CSS selector for the element(s) inside the node that start a drag. None = whole node.
+    pub drag_handle: Option<String>,
 }
 
 impl<T: Default> Node<T> {
@@ -256,8 +316,11 @@ impl<T: Default> Node<T> {
             id: id.into(),
             label: None,
             position: Position::new(x, y),
+            position_absolute: Position::new(x, y),
+            parent_id: None,
             width: None,
             height: None,
+            measured: None,
             data: T::default(),
             selected: false,
             selectable: true,
@@ -273,6 +336,9 @@ impl<T: Default> Node<T> {
             class: String::new(),
             style: HashMap::new(),
             extent: None,
+            extent_parent: false,
+            collapsed: false,
+            drag_handle: None,
         }
     }
 
@@ -282,8 +348,11 @@ impl<T: Default> Node<T> {
             id: id.into(),
             label: None,
             position: Position::new(x, y),
+            position_absolute: Position::new(x, y),
+            parent_id: None,
             width: None,
             height: None,
+            measured: None,
             data: T::default(),
             selected: false,
             selectable: true,
@@ -296,8 +365,18 @@ impl<T: Default> Node<T> {
             class: String::new(),
             style: HashMap::new(),
             extent: None,
+            extent_parent: false,
+            collapsed: false,
+            drag_handle: None,
         }
     }
+
+    /// Create a group node, a container rendered behind its children.
+    pub fn new_group(id: impl Into<String>, x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self::new_without_handles(id, x, y)
+            .with_type("group")
+            .with_dimensions(width, height)
+    }
 }
 
 impl<T> Node<T> {
@@ -355,6 +434,30 @@ impl<T> Node<T> {
         self
     }
 
+    /// Set the parent node. The position becomes relative to the parent.
+    pub fn with_parent(mut self, parent_id: impl Into<String>) -> Self {
+        self.parent_id = Some(parent_id.into());
+        self
+    }
+
+    /// Set whether the node is confined to the bounds of its parent.
+    pub fn with_extent_parent(mut self, extent_parent: bool) -> Self {
+        self.extent_parent = extent_parent;
+        self
+    }
+
+    /// Restrict dragging to elements matching a CSS selector, e.g. `".drag-handle"`.
+    pub fn with_drag_handle(mut self, selector: impl Into<String>) -> Self {
+        self.drag_handle = Some(selector.into());
+        self
+    }
+
+    /// Set whether the node is collapsed, hiding its descendants.
+    pub fn with_collapsed(mut self, collapsed: bool) -> Self {
+        self.collapsed = collapsed;
+        self
+    }
+
     /// Set the node dimensions.
     pub fn with_dimensions(mut self, width: f64, height: f64) -> Self {
         self.width = Some(width);
@@ -435,36 +538,126 @@ impl<T> Node<T> {
         self.handles.iter().filter(|h| h.kind == HandleKind::Target)
     }
 
+    /// Get the node size, preferring the measured rendered size over explicit
+    /// dimensions and falling back to the defaults. Collapsed nodes use the default size.
+    pub fn dimensions(&self) -> (f64, f64) {
+        if self.collapsed {
+            return (DEFAULT_NODE_WIDTH, DEFAULT_NODE_HEIGHT);
+        }
+        self.measured.unwrap_or((
+            self.width.unwrap_or(DEFAULT_NODE_WIDTH),
+            self.height.unwrap_or(DEFAULT_NODE_HEIGHT),
+        ))
+    }
+
+    /// Check whether the node is a group container.
+    pub fn is_group(&self) -> bool {
+        self.node_type == "group"
+    }
+
+    /// Get the position of the node in flow coordinates, resolving parent offsets.
+    pub fn absolute_position(&self) -> Position {
+        if self.parent_id.is_some() {
+            self.position_absolute
+        } else {
+            self.position
+        }
+    }
+
+    /// Get the bounds of the node in absolute flow coordinates.
+    pub fn rect(&self) -> SelectionRect {
+        let (width, height) = self.dimensions();
+        let position = self.absolute_position();
+        SelectionRect {
+            x: position.x,
+            y: position.y,
+            width,
+            height,
+        }
+    }
+
     /// Get the center position of the node.
     pub fn center(&self) -> Position {
-        let w = self.width.unwrap_or(150.0);
-        let h = self.height.unwrap_or(40.0);
-        Position::new(self.position.x + w / 2.0, self.position.y + h / 2.0)
+        let (w, h) = self.dimensions();
+        let position = self.absolute_position();
+        Position::new(position.x + w / 2.0, position.y + h / 2.0)
+    }
+
+    /// Get the point where the line from this node's center towards `point` crosses
+    /// the node's boundary, along with the side it crosses. Used for floating edges.
+    pub fn boundary_intersection(&self, point: Position) -> (Position, HandlePosition) {
+        let (w, h) = self.dimensions();
+        let center = self.center();
+
+        let half_w = w / 2.0;
+        let half_h = h / 2.0;
+        let dx = point.x - center.x;
+        let dy = point.y - center.y;
+        if dx.abs() < f64::EPSILON && dy.abs() < f64::EPSILON {
+            return (self.handle_position(HandlePosition::Top), HandlePosition::Top);
+        }
+
+        // A node without area has no sides to cross, so its edges leave from the center
+        if !(half_w > 0.0 && half_h > 0.0) {
+            let side = if dx.abs() >= dy.abs() {
+                if dx < 0.0 {
+                    HandlePosition::Left
+                } else {
+                    HandlePosition::Right
+                }
+            } else if dy < 0.0 {
+                HandlePosition::Top
+            } else {
+                HandlePosition::Bottom
+            };
+            return (center, side);
+        }
+
+        // Scale the direction vector so it touches the nearest side of the rectangle
+        let scale = 1.0 / (dx.abs() / half_w).max(dy.abs() / half_h);
+        let intersection = Position::new(center.x + dx * scale, center.y + dy * scale);
+
+        // Pick the side that was hit, preferring the dominant axis at corners
+        let side = if (dx.abs() / half_w) >= (dy.abs() / half_h) {
+            if dx < 0.0 {
+                HandlePosition::Left
+            } else {
+                HandlePosition::Right
+            }
+        } else if dy < 0.0 {
+            HandlePosition::Top
+        } else {
+            HandlePosition::Bottom
+        };
+
+        (intersection, side)
     }
 
     /// Get handle position for a given handle position type (legacy).
     pub fn handle_position(&self, handle_pos: HandlePosition) -> Position {
-        let w = self.width.unwrap_or(150.0);
-        let h = self.height.unwrap_or(40.0);
+        let (w, h) = self.dimensions();
         let offset = handle_pos.offset(w, h);
-        Position::new(self.position.x + offset.x, self.position.y + offset.y)
+        let position = self.absolute_position();
+        Position::new(position.x + offset.x, position.y + offset.y)
     }
 
     /// Get handle position by handle ID.
     pub fn handle_position_by_id(&self, handle_id: &str) -> Option<Position> {
-        let w = self.width.unwrap_or(150.0);
-        let h = self.height.unwrap_or(40.0);
+        let (w, h) = self.dimensions();
         self.get_handle(handle_id)
-            .map(|handle| handle.absolute_position(self.position, w, h))
+            .map(|handle| handle.absolute_position(self.absolute_position(), w, h))
     }
 
     /// Get handle position and direction by handle ID.
     /// Returns (absolute_position, handle_direction) for edge routing.
     pub fn handle_info_by_id(&self, handle_id: &str) -> Option<(Position, HandlePosition)> {
-        let w = self.width.unwrap_or(150.0);
-        let h = self.height.unwrap_or(40.0);
-        self.get_handle(handle_id)
-            .map(|handle| (handle.absolute_position(self.position, w, h), handle.position))
+        let (w, h) = self.dimensions();
+        self.get_handle(handle_id).map(|handle| {
+            (
+                handle.absolute_position(self.absolute_position(), w, h),
+                handle.position,
+            )
+        })
     }
 }
 
@@ -478,6 +671,104 @@ pub enum EdgeType {
     SmoothStep,
 }
 
+/// Animation mode for an edge.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum EdgeAnimation {
+    /// No animation.
+    #[default]
+    None,
+    /// Dashed stroke moving along the path.
+    Dash,
+    /// Dots travelling along the path from source to target.
+    Particles(ParticleOptions),
+    /// Stroke pulsing in opacity and width.
+    Pulse,
+    /// Stroke fading from one color at the source to another at the target.
+    Gradient {
+        /// Color at the source end.
+        source_color: String,
+        /// Color at the target end.
+        target_color: String,
+    },
+}
+
+impl EdgeAnimation {
+    /// Create a particle animation with default options.
+    pub fn particles() -> Self {
+        EdgeAnimation::Particles(ParticleOptions::default())
+    }
+
+    /// Create a gradient animation between two colors.
+    pub fn gradient(source_color: impl Into<String>, target_color: impl Into<String>) -> Self {
+        EdgeAnimation::Gradient {
+            source_color: source_color.into(),
+            target_color: target_color.into(),
+        }
+    }
+}
+
+/// Options for the particle edge animation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParticleOptions {
+    /// Number of particles on the path at once.
+    pub count: usize,
+    /// Particle speed in flow pixels per second.
+    pub speed: f64,
+    /// Particle color (defaults to the edge's status color, or its stroke color when idle).
+    pub color: Option<String>,
+    /// Particle radius in pixels.
+    pub radius: f64,
+}
+
+impl Default for ParticleOptions {
+    fn default() -> Self {
+        Self {
+            count: 3,
+            speed: 100.0,
+            color: None,
+            radius: 3.0,
+        }
+    }
+}
+
+impl ParticleOptions {
+    /// Set the number of particles.
+    pub fn with_count(mut self, count: usize) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Set the particle speed in pixels per second.
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Set the particle color.
+    pub fn with_color(mut self, color: impl Into<String>) -> Self {
+        self.color = Some(color.into());
+        self
+    }
+
+    /// Set the particle radius.
+    pub fn with_radius(mut self, radius: f64) -> Self {
+        self.radius = radius;
+        self
+    }
+}
+
+/// Run-time status of an edge, mapped to built-in styles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EdgeStatus {
+    /// Nothing is happening on the edge. Uses the edge's own stroke.
+    #[default]
+    Idle,
+    /// Data is flowing along the edge. Drawn with a blue stroke.
+    Active,
+    /// The connection failed. Drawn with a red, dashed stroke.
+    Error,
+}
+
 /// An edge connecting two nodes.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Edge {
@@ -497,8 +788,14 @@ pub struct Edge {
     pub target_handle_id: Option<HandleId>,
     /// Edge type for rendering.
     pub edge_type: EdgeType,
-    /// Whether the edge is animated.
+    /// Whether the edge is animated (legacy, equivalent to `EdgeAnimation::Dash`).
     pub animated: bool,
+    /// Animation mode for the edge.
+    pub animation: EdgeAnimation,
+    /// Run-time status of the edge.
+    pub status: EdgeStatus,
+    /// Whether the edge floats, ignoring handles and attaching to the nearest node boundary.
+    pub floating: bool,
     /// Whether the edge is selected.
     pub selected: bool,
     /// Whether the edge is selectable.
@@ -511,6 +808,8 @@ pub struct Edge {
     pub stroke: String,
     /// Edge width.
     pub stroke_width: f64,
+    /// Width of the invisible path used for hit testing.
+    pub interaction_width: f64,
     /// Additional CSS classes.
     pub class: String,
 }
@@ -528,12 +827,16 @@ impl Edge {
             target_handle_id: None,
             edge_type: EdgeType::default(),
             animated: false,
+            animation: EdgeAnimation::None,
+            status: EdgeStatus::Idle,
+            floating: false,
             selected: false,
             selectable: true,
             deletable: true,
             label: None,
             stroke: "#b1b1b7".to_string(),
             stroke_width: 2.0,
+            interaction_width: 20.0,
             class: String::new(),
         }
     }
@@ -556,12 +859,16 @@ impl Edge {
             target_handle_id: Some(target_handle.into()),
             edge_type: EdgeType::default(),
             animated: false,
+            animation: EdgeAnimation::None,
+            status: EdgeStatus::Idle,
+            floating: false,
             selected: false,
             selectable: true,
             deletable: true,
             label: None,
             stroke: "#b1b1b7".to_string(),
             stroke_width: 2.0,
+            interaction_width: 20.0,
             class: String::new(),
         }
     }
@@ -614,6 +921,24 @@ impl Edge {
         self
     }
 
+    /// Set the animation mode.
+    pub fn with_animation(mut self, animation: EdgeAnimation) -> Self {
+        self.animation = animation;
+        self
+    }
+
+    /// Set the run-time status.
+    pub fn with_status(mut self, status: EdgeStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Set whether the edge floats between node boundaries instead of using handles.
+    pub fn with_floating(mut self, floating: bool) -> Self {
+        self.floating = floating;
+        self
+    }
+
     /// Set the edge label.
     pub fn with_label(mut self, label: impl Into<String>) -> Self {
         self.label = Some(label.into());
@@ -632,6 +957,12 @@ impl Edge {
         self
     }
 
+    /// Set the width of the invisible interaction path.
+    pub fn with_interaction_width(mut self, width: f64) -> Self {
+        self.interaction_width = width;
+        self
+    }
+
     /// Add a CSS class.
     pub fn with_class(mut self, class: impl Into<String>) -> Self {
         self.class = class.into();
@@ -659,14 +990,45 @@ pub enum FlowEvent {
     NodeClick(NodeId),
     /// Node was double-clicked.
     NodeDoubleClick(NodeId),
-    /// Node drag started.
-    NodeDragStart(NodeId),
-    /// Node was dragged.
-    NodeDrag { id: NodeId, position: Position },
-    /// Node drag ended.
-    NodeDragEnd(NodeId),
+    /// Node drag started, with the start positions of all moved nodes.
+    NodeDragStart(NodeDragEvent),
+    /// Nodes were dragged, with their new positions.
+    NodeDrag(NodeDragEvent),
+    /// Node drag ended, with the final positions of all moved nodes.
+    NodeDragEnd(NodeDragEvent),
+    /// Node resize started.
+    NodeResizeStart(NodeId),
+    /// Node was resized.
+    NodeResize {
+        id: NodeId,
+        position: Position,
+        width: f64,
+        height: f64,
+    },
+    /// Node resize ended.
+    NodeResizeEnd(NodeId),
+    /// Node was collapsed or expanded.
+    NodeCollapse { id: NodeId, collapsed: bool },
+    /// A group was opened as its own canvas.
+    SubflowEnter(NodeId),
+    /// An opened group was closed.
+    SubflowExit(NodeId),
+    /// Node was dropped into or out of a group.
+    NodeReparent {
+        id: NodeId,
+        old_parent: Option<NodeId>,
+        new_parent: Option<NodeId>,
+    },
     /// Edge was clicked.
     EdgeClick(EdgeId),
+    /// Edge was double-clicked.
+    EdgeDoubleClick { id: EdgeId, position: Position },
+    /// Mouse entered an edge.
+    EdgeMouseEnter { id: EdgeId, position: Position },
+    /// Mouse left an edge.
+    EdgeMouseLeave { id: EdgeId, position: Position },
+    /// Edge context menu was requested.
+    EdgeContextMenu { id: EdgeId, position: Position },
     /// Connection was started.
     ConnectStart {
         node_id: NodeId,
@@ -692,6 +1054,8 @@ pub enum FlowEvent {
     NodesDelete(Vec<NodeId>),
     /// Edges were deleted.
     EdgesDelete(Vec<EdgeId>),
+    /// A node or edge label was edited in place.
+    LabelChange(LabelChange),
 }
 
 /// Snap grid configuration.
@@ -831,13 +1195,24 @@ impl SelectionRect {
 
     /// Check if a node intersects with this rectangle.
     pub fn intersects_node<T>(&self, node: &Node<T>) -> bool {
-        let node_rect = SelectionRect {
-            x: node.position.x,
-            y: node.position.y,
-            width: node.width.unwrap_or(150.0),
-            height: node.height.unwrap_or(40.0),
-        };
-        self.intersects(&node_rect)
+        self.intersects(&node.rect())
+    }
+
+    /// Get the distance from a point to the rectangle, or 0 if the point is inside.
+    pub fn distance_to(&self, x: f64, y: f64) -> f64 {
+        let dx = (self.x - x).max(x - (self.x + self.width)).max(0.0);
+        let dy = (self.y - y).max(y - (self.y + self.height)).max(0.0);
+        (dx * dx + dy * dy).sqrt()
+    }
+
+    /// Get this rectangle grown by `margin` on every side.
+    pub fn expanded(&self, margin: f64) -> SelectionRect {
+        SelectionRect {
+            x: self.x - margin,
+            y: self.y - margin,
+            width: self.width + margin * 2.0,
+            height: self.height + margin * 2.0,
+        }
     }
 }
 
@@ -905,6 +1280,377 @@ impl NodeExtent {
     }
 }
 
+/// How much of the flow is drawn at the current zoom level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DetailLevel {
+    /// Node content, handles and edge labels.
+    #[default]
+    Full,
+    /// Node content only; handles and edge labels are hidden.
+    Reduced,
+    /// Nodes are drawn as plain rectangles with their label.
+    Placeholder,
+}
+
+/// Zoom levels below which the flow draws less detail.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelOfDetail {
+    /// Below this zoom, handles and edge labels are hidden.
+    pub reduced_below: f64,
+    /// Below this zoom, nodes are drawn as placeholders.
+    pub placeholder_below: f64,
+}
+
+impl Default for LevelOfDetail {
+    fn default() -> Self {
+        Self {
+            reduced_below: 0.5,
+            placeholder_below: 0.25,
+        }
+    }
+}
+
+impl LevelOfDetail {
+    /// Create level-of-detail thresholds.
+    pub fn new(reduced_below: f64, placeholder_below: f64) -> Self {
+        Self {
+            reduced_below,
+            placeholder_below,
+        }
+    }
+
+    /// Get the detail level to draw at a zoom level.
+    pub fn level(&self, zoom: f64) -> DetailLevel {
+        if zoom < self.placeholder_below {
+            DetailLevel::Placeholder
+        } else if zoom < self.reduced_below {
+            DetailLevel::Reduced
+        } else {
+            DetailLevel::Full
+        }
+    }
+}
+
+/// Alignment guides shown while dragging a node.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HelperLines {
+    /// X coordinate of a vertical guide, in flow coordinates.
+    pub vertical: Option<f64>,
+    /// Y coordinate of a horizontal guide, in flow coordinates.
+    pub horizontal: Option<f64>,
+    /// Equal-spacing indicators as (from, to) segments in flow coordinates.
+    pub spacing: Vec<(Position, Position)>,
+}
+
+impl HelperLines {
+    /// Check whether there is nothing to show.
+    pub fn is_empty(&self) -> bool {
+        self.vertical.is_none() && self.horizontal.is_none() && self.spacing.is_empty()
+    }
+}
+
+/// A handle found near a point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandleMatch {
+    /// Node the handle belongs to.
+    pub node_id: NodeId,
+    /// ID of the handle on its node.
+    pub handle_id: HandleId,
+    /// Whether the handle is a source or a target.
+    pub kind: HandleKind,
+    /// Position of the handle in flow coordinates.
+    pub position: Position,
+    /// Distance from the point to the handle, in flow pixels.
+    pub distance: f64,
+}
+
+/// Nodes moved by a drag gesture.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeDragEvent {
+    /// The node under the pointer.
+    pub id: NodeId,
+    /// All moved nodes with their positions, relative to the parent each node had when
+    /// the drag started. A node dropped into another group is reported separately with
+    /// `NodeReparent`.
+    pub nodes: Vec<(NodeId, Position)>,
+}
+
+/// An element whose label can be edited in place.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LabelTarget {
+    /// The label of the node with this ID.
+    Node(NodeId),
+    /// The label of the edge with this ID.
+    Edge(EdgeId),
+}
+
+/// A label edited in place.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelChange {
+    /// The edited node or edge.
+    pub target: LabelTarget,
+    /// Label before the edit.
+    pub old_label: Option<String>,
+    /// Label after the edit (`None` if it was cleared).
+    pub new_label: Option<String>,
+}
+
+/// A node moved into or out of a group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeReparent {
+    /// The moved node.
+    pub id: NodeId,
+    /// Parent before the move (`None` = top level).
+    pub old_parent: Option<NodeId>,
+    /// Parent after the move (`None` = top level).
+    pub new_parent: Option<NodeId>,
+}
+
+/// A group opened as its own canvas (drill-down), with the viewport to restore on exit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubflowLevel {
+    /// The opened group.
+    pub group_id: NodeId,
+    /// Viewport before the group was opened.
+    pub viewport: Viewport,
+}
+
+/// How to line up selected nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeAlignment {
+    /// Left edges.
+    Left,
+    /// Horizontal centres.
+    Center,
+    /// Right edges.
+    Right,
+    /// Top edges.
+    Top,
+    /// Vertical centres.
+    Middle,
+    /// Bottom edges.
+    Bottom,
+}
+
+/// Axis along which selected nodes are spaced evenly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DistributeDirection {
+    /// Equal gaps from left to right.
+    Horizontal,
+    /// Equal gaps from top to bottom.
+    Vertical,
+}
+
+/// Direction of a node resize grip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResizeDirection {
+    TopLeft,
+    Top,
+    TopRight,
+    Right,
+    BottomRight,
+    Bottom,
+    BottomLeft,
+    Left,
+}
+
+impl ResizeDirection {
+    /// All grip directions.
+    pub const ALL: [ResizeDirection; 8] = [
+        ResizeDirection::TopLeft,
+        ResizeDirection::Top,
+        ResizeDirection::TopRight,
+        ResizeDirection::Right,
+        ResizeDirection::BottomRight,
+        ResizeDirection::Bottom,
+        ResizeDirection::BottomLeft,
+        ResizeDirection::Left,
+    ];
+
+    /// Whether this grip sits on a corner of the node.
+    pub fn is_corner(&self) -> bool {
+        matches!(
+            self,
+            ResizeDirection::TopLeft
+                | ResizeDirection::TopRight
+                | ResizeDirection::BottomRight
+                | ResizeDirection::BottomLeft
+        )
+    }
+
+    /// Whether this grip moves the left side of the node.
+    pub fn moves_left(&self) -> bool {
+        matches!(
+            self,
+            ResizeDirection::TopLeft | ResizeDirection::Left | ResizeDirection::BottomLeft
+        )
+    }
+
+    /// Whether this grip moves the right side of the node.
+    pub fn moves_right(&self) -> bool {
+        matches!(
+            self,
+            ResizeDirection::TopRight | ResizeDirection::Right | ResizeDirection::BottomRight
+        )
+    }
+
+    /// Whether this grip moves the top side of the node.
+    pub fn moves_top(&self) -> bool {
+        matches!(
+            self,
+            ResizeDirection::TopLeft | ResizeDirection::Top | ResizeDirection::TopRight
+        )
+    }
+
+    /// Whether this grip moves the bottom side of the node.
+    pub fn moves_bottom(&self) -> bool {
+        matches!(
+            self,
+            ResizeDirection::BottomLeft | ResizeDirection::Bottom | ResizeDirection::BottomRight
+        )
+    }
+
+    /// CSS class suffix for this grip.
+    pub fn class_name(&self) -> &'static str {
+        match self {
+            ResizeDirection::TopLeft => "top-left",
+            ResizeDirection::Top => "top",
+            ResizeDirection::TopRight => "top-right",
+            ResizeDirection::Right => "right",
+            ResizeDirection::BottomRight => "bottom-right",
+            ResizeDirection::Bottom => "bottom",
+            ResizeDirection::BottomLeft => "bottom-left",
+            ResizeDirection::Left => "left",
+        }
+    }
+
+    /// CSS cursor for this grip.
+    pub fn cursor(&self) -> &'static str {
+        match self {
+            ResizeDirection::TopLeft | ResizeDirection::BottomRight => "nwse-resize",
+            ResizeDirection::TopRight | ResizeDirection::BottomLeft => "nesw-resize",
+            ResizeDirection::Top | ResizeDirection::Bottom => "ns-resize",
+            ResizeDirection::Left | ResizeDirection::Right => "ew-resize",
+        }
+    }
+}
+
+/// Size limits applied while resizing a node.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResizeConstraints {
+    /// Minimum width.
+    pub min_width: f64,
+    /// Minimum height.
+    pub min_height: f64,
+    /// Maximum width.
+    pub max_width: f64,
+    /// Maximum height.
+    pub max_height: f64,
+    /// Whether to keep the width/height ratio the node had when the resize started.
+    pub keep_aspect_ratio: bool,
+}
+
+impl Default for ResizeConstraints {
+    fn default() -> Self {
+        Self {
+            min_width: 10.0,
+            min_height: 10.0,
+            max_width: f64::MAX,
+            max_height: f64::MAX,
+            keep_aspect_ratio: false,
+        }
+    }
+}
+
+/// Position and size of a node during a resize.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ResizeParams {
+    /// Top-left position of the node.
+    pub position: Position,
+    /// Width of the node.
+    pub width: f64,
+    /// Height of the node.
+    pub height: f64,
+}
+
+impl ResizeParams {
+    /// Compute the new position and size after dragging a grip by (dx, dy) flow pixels.
+    /// The side opposite the grip stays fixed.
+    pub fn resized(
+        &self,
+        direction: ResizeDirection,
+        dx: f64,
+        dy: f64,
+        constraints: &ResizeConstraints,
+        snap_grid: &SnapGrid,
+    ) -> ResizeParams {
+        let left = self.position.x;
+        let top = self.position.y;
+        let right = left + self.width;
+        let bottom = top + self.height;
+        // Dragged corners, snapped to the grid
+        let top_left = snap_grid.snap(Position::new(left + dx, top + dy));
+        let bottom_right = snap_grid.snap(Position::new(right + dx, bottom + dy));
+
+        let mut width = if direction.moves_left() {
+            right - top_left.x
+        } else if direction.moves_right() {
+            bottom_right.x - left
+        } else {
+            self.width
+        };
+        let mut height = if direction.moves_top() {
+            bottom - top_left.y
+        } else if direction.moves_bottom() {
+            bottom_right.y - top
+        } else {
+            self.height
+        };
+
+        if constraints.keep_aspect_ratio && self.width > 0.0 && self.height > 0.0 {
+            let scale_x = width / self.width;
+            let scale_y = height / self.height;
+            // Corners follow whichever axis moved further
+            let scale = if direction.is_corner() {
+                if (scale_x - 1.0).abs() >= (scale_y - 1.0).abs() {
+                    scale_x
+                } else {
+                    scale_y
+                }
+            } else if direction.moves_left() || direction.moves_right() {
+                scale_x
+            } else {
+                scale_y
+            };
+            let min_scale =
+                (constraints.min_width / self.width).max(constraints.min_height / self.height);
+            let max_scale =
+                (constraints.max_width / self.width).min(constraints.max_height / self.height);
+            let scale = scale.clamp(min_scale, max_scale.max(min_scale));
+            width = self.width * scale;
+            height = self.height * scale;
+        } else {
+            width = width.clamp(
+                constraints.min_width,
+                constraints.max_width.max(constraints.min_width),
+            );
+            height = height.clamp(
+                constraints.min_height,
+                constraints.max_height.max(constraints.min_height),
+            );
+        }
+
+        let x = if direction.moves_left() { right - width } else { left };
+        let y = if direction.moves_top() { bottom - height } else { top };
+
+        ResizeParams {
+            position: Position::new(x, y),
+            width,
+            height,
+        }
+    }
+}
+
 /// Interactivity configuration for the flow.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct InteractivityConfig {
@@ -961,6 +1707,8 @@ pub struct DefaultEdgeOptions {
     pub stroke_width: f64,
     /// Default animated state.
     pub animated: bool,
+    /// Default floating state.
+    pub floating: bool,
 }
 
 impl Default for DefaultEdgeOptions {
@@ -970,10 +1718,88 @@ impl Default for DefaultEdgeOptions {
             stroke: "#b1b1b7".to_string(),
             stroke_width: 2.0,
             animated: false,
+            floating: false,
         }
     }
 }
 
+/// Which nodes `fit_view` should bring into view.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum FitViewNodes {
+    /// All visible nodes.
+    #[default]
+    All,
+    /// The selected nodes.
+    Selected,
+    /// The given nodes.
+    Ids(Vec<NodeId>),
+}
+
+/// Options for fitting the viewport to nodes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FitViewOptions {
+    /// Nodes to fit.
+    pub nodes: FitViewNodes,
+    /// Space around the nodes, as a ratio of their bounding box size.
+    pub padding: f64,
+    /// Minimum zoom level.
+    pub min_zoom: f64,
+    /// Maximum zoom level.
+    pub max_zoom: f64,
+    /// Duration of the transition in milliseconds (0 jumps straight there).
+    pub duration: u32,
+}
+
+impl Default for FitViewOptions {
+    fn default() -> Self {
+        Self {
+            nodes: FitViewNodes::All,
+            padding: 0.1,
+            min_zoom: 0.1,
+            max_zoom: 1.0,
+            duration: 0,
+        }
+    }
+}
+
+impl FitViewOptions {
+    /// Create default fit view options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fit only the given nodes.
+    pub fn with_nodes(mut self, ids: Vec<NodeId>) -> Self {
+        self.nodes = FitViewNodes::Ids(ids);
+        self
+    }
+
+    /// Fit only the selected nodes.
+    pub fn with_selected(mut self) -> Self {
+        self.nodes = FitViewNodes::Selected;
+        self
+    }
+
+    /// Set the padding ratio.
+    pub fn with_padding(mut self, padding: f64) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Set the zoom range.
+    pub fn with_zoom_range(mut self, min_zoom: f64, max_zoom: f64) -> Self {
+        self.min_zoom = min_zoom;
+        self.max_zoom = max_zoom;
+        self
+    }
+
+    /// Animate the transition over `duration` milliseconds.
+    pub fn with_duration(mut self, duration: u32) -> Self {
+        self.duration = duration;
+        self
+    }
+}
+
 /// Copy/paste clipboard data.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ClipboardData<T: Clone> {
@@ -991,3 +1817,33 @@ impl<T: Clone> Default for ClipboardData<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boundary_intersection_hits_the_facing_side() {
+        let node: Node = Node::new("a", 0.0, 0.0).with_dimensions(100.0, 50.0);
+        let (point, side) = node.boundary_intersection(Position::new(300.0, 25.0));
+        assert_eq!(point, Position::new(100.0, 25.0));
+        assert_eq!(side, HandlePosition::Right);
+    }
+
+    #[test]
+    fn boundary_intersection_of_degenerate_nodes_is_finite() {
+        let node: Node = Node::new("a", 10.0, 20.0).with_dimensions(0.0, 0.0);
+        let (point, side) = node.boundary_intersection(Position::new(10.0, 100.0));
+        assert_eq!(point, Position::new(10.0, 20.0));
+        assert_eq!(side, HandlePosition::Bottom);
+
+        let flat: Node = Node::new("b", 0.0, 0.0).with_dimensions(100.0, 0.0);
+        let (point, side) = flat.boundary_intersection(Position::new(30.0, -40.0));
+        assert_eq!(point, Position::new(50.0, 0.0));
+        assert_eq!(side, HandlePosition::Top);
+
+        // Coincident centers
+        let (point, _) = node.boundary_intersection(node.center());
+        assert!(point.x.is_finite() && point.y.is_finite());
+    }
+}
@@ -1,6 +1,7 @@
 //! Utility functions for dioxus-flow.
 
-use crate::types::{EdgeType, Position};
+use crate::types::{EdgeType, HandlePosition, HelperLines, Node, Position};
+use wasm_bindgen::JsCast;
 
 /// Calculate the path for a bezier edge.
 pub fn get_bezier_path(
@@ -175,6 +176,299 @@ pub fn get_edge_path(
     }
 }
 
+/// Calculate the length of a path made of `M`, `L`, `Q` and `C` commands, as produced by
+/// [`get_edge_path`]. Curves are approximated with straight segments.
+pub fn get_path_length(path: &str) -> f64 {
+    const CURVE_SEGMENTS: usize = 16;
+
+    let mut length = 0.0;
+    let mut current = Position::new(0.0, 0.0);
+    let mut command = 'M';
+    let mut points: Vec<Position> = Vec::new();
+    for token in path.split_whitespace() {
+        let Some((x, y)) = token.split_once(',') else {
+            command = token.chars().next().unwrap_or('M');
+            points.clear();
+            continue;
+        };
+        let (Ok(x), Ok(y)) = (x.parse(), y.parse()) else {
+            continue;
+        };
+        points.push(Position::new(x, y));
+        let needed = match command {
+            'Q' => 2,
+            'C' => 3,
+            _ => 1,
+        };
+        if points.len() < needed {
+            continue;
+        }
+        match command {
+            'L' => length += distance(current, points[0]),
+            'Q' | 'C' => {
+                let mut controls = vec![current];
+                controls.extend_from_slice(&points);
+                let mut previous = current;
+                for i in 1..=CURVE_SEGMENTS {
+                    let point = bezier_point(&controls, i as f64 / CURVE_SEGMENTS as f64);
+                    length += distance(previous, point);
+                    previous = point;
+                }
+            }
+            _ => {}
+        }
+        current = points[needed - 1];
+        points.clear();
+    }
+    length
+}
+
+/// Evaluate a bezier curve with the given control points at `t`.
+fn bezier_point(controls: &[Position], t: f64) -> Position {
+    let mut points = controls.to_vec();
+    while points.len() > 1 {
+        points = points
+            .windows(2)
+            .map(|pair| {
+                Position::new(
+                    pair[0].x + (pair[1].x - pair[0].x) * t,
+                    pair[0].y + (pair[1].y - pair[0].y) * t,
+                )
+            })
+            .collect();
+    }
+    points[0]
+}
+
+/// Escape an ID for use in an SVG `id` attribute and `url(#...)` reference.
+///
+/// Characters other than ASCII letters, digits and `-` are written as `_<hex code>_`,
+/// so distinct IDs stay distinct.
+pub fn escape_svg_id(id: &str) -> String {
+    id.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c.to_string()
+            } else {
+                format!("_{:x}_", c as u32)
+            }
+        })
+        .collect()
+}
+
+/// Calculate the endpoints and directions of a floating edge between two nodes.
+///
+/// Each endpoint is where the line between the node centers crosses that node's
+/// boundary. Returns (source_position, source_direction, target_position, target_direction).
+pub fn get_floating_edge_params<T>(
+    source: &Node<T>,
+    target: &Node<T>,
+) -> (Position, HandlePosition, Position, HandlePosition) {
+    let (source_pos, source_dir) = source.boundary_intersection(target.center());
+    let (target_pos, target_dir) = target.boundary_intersection(source.center());
+    (source_pos, source_dir, target_pos, target_dir)
+}
+
+/// Check whether an event target, or one of its ancestors inside `boundary`,
+/// matches a CSS selector. Used for the `nodrag`/`nopan`/`nowheel` conventions.
+pub(crate) fn target_matches(
+    target: Option<web_sys::EventTarget>,
+    boundary: Option<web_sys::EventTarget>,
+    selector: &str,
+) -> bool {
+    let Some(element) = target.and_then(|t| t.dyn_into::<web_sys::Element>().ok()) else {
+        return false;
+    };
+    let Ok(Some(matched)) = element.closest(selector) else {
+        return false;
+    };
+    match boundary.and_then(|b| b.dyn_into::<web_sys::Node>().ok()) {
+        Some(boundary) => boundary.contains(Some(&matched)),
+        None => true,
+    }
+}
+
+/// Check whether an event target is a form control or editable element that
+/// should keep keyboard input to itself.
+pub(crate) fn is_editable_target(target: Option<web_sys::EventTarget>) -> bool {
+    let Some(element) = target.and_then(|t| t.dyn_into::<web_sys::HtmlElement>().ok()) else {
+        return false;
+    };
+    matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT")
+        || element.is_content_editable()
+}
+
+/// Find alignment guides for a dragged rectangle (x, y, width, height) against other
+/// rectangles, along with the shift that snaps it onto them.
+///
+/// Edges and centres align within `threshold`; failing that, the rectangle snaps to
+/// spacing equal to the gap between its neighbours.
+pub fn get_helper_lines(
+    dragged: (f64, f64, f64, f64),
+    others: &[(f64, f64, f64, f64)],
+    threshold: f64,
+) -> (HelperLines, Position) {
+    let (x, y, w, h) = dragged;
+    let mut lines = HelperLines::default();
+    let mut shift = Position::new(0.0, 0.0);
+
+    // Edge and centre alignment, per axis
+    let mut best_x: Option<(f64, f64)> = None;
+    let mut best_y: Option<(f64, f64)> = None;
+    for &(ox, oy, ow, oh) in others {
+        for a in [x, x + w / 2.0, x + w] {
+            for b in [ox, ox + ow / 2.0, ox + ow] {
+                let diff = b - a;
+                if diff.abs() <= threshold && best_x.is_none_or(|(d, _)| diff.abs() < d.abs()) {
+                    best_x = Some((diff, b));
+                }
+            }
+        }
+        for a in [y, y + h / 2.0, y + h] {
+            for b in [oy, oy + oh / 2.0, oy + oh] {
+                let diff = b - a;
+                if diff.abs() <= threshold && best_y.is_none_or(|(d, _)| diff.abs() < d.abs()) {
+                    best_y = Some((diff, b));
+                }
+            }
+        }
+    }
+    if let Some((diff, line)) = best_x {
+        shift.x = diff;
+        lines.vertical = Some(line);
+    }
+    if let Some((diff, line)) = best_y {
+        shift.y = diff;
+        lines.horizontal = Some(line);
+    }
+
+    // Equal spacing along each axis, among neighbours overlapping on the other axis
+    if best_x.is_none() {
+        let row: Vec<(f64, f64)> = others
+            .iter()
+            .filter(|o| o.1 < y + h && o.1 + o.3 > y)
+            .map(|o| (o.0, o.0 + o.2))
+            .collect();
+        if let Some((diff, gaps)) = equal_spacing((x, x + w), &row, threshold) {
+            shift.x = diff;
+            let cross = y + shift.y + h / 2.0;
+            lines.spacing.extend(
+                gaps.into_iter()
+                    .map(|(a, b)| (Position::new(a, cross), Position::new(b, cross))),
+            );
+        }
+    }
+    if best_y.is_none() {
+        let column: Vec<(f64, f64)> = others
+            .iter()
+            .filter(|o| o.0 < x + w && o.0 + o.2 > x)
+            .map(|o| (o.1, o.1 + o.3))
+            .collect();
+        if let Some((diff, gaps)) = equal_spacing((y, y + h), &column, threshold) {
+            shift.y = diff;
+            let cross = x + shift.x + w / 2.0;
+            lines.spacing.extend(
+                gaps.into_iter()
+                    .map(|(a, b)| (Position::new(cross, a), Position::new(cross, b))),
+            );
+        }
+    }
+
+    (lines, shift)
+}
+
+/// Find the smallest shift (within `threshold`) of the interval `dragged` that makes
+/// its gap to a neighbour equal to the gap between two other adjacent intervals.
+/// Returns the shift and the equal gaps as (start, end) pairs.
+fn equal_spacing(
+    dragged: (f64, f64),
+    others: &[(f64, f64)],
+    threshold: f64,
+) -> Option<(f64, Vec<(f64, f64)>)> {
+    let (start, end) = dragged;
+    let len = end - start;
+    let mut sorted = others.to_vec();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut best: Option<(f64, Vec<(f64, f64)>)> = None;
+    let mut consider = |diff: f64, gaps: Vec<(f64, f64)>| {
+        if diff.abs() <= threshold && best.as_ref().is_none_or(|(d, _)| diff.abs() < d.abs()) {
+            best = Some((diff, gaps));
+        }
+    };
+    // Nothing may sit between the dragged interval and its neighbour. The intervals
+    // starting before `to` are a prefix of `sorted`, so it is enough to check the
+    // furthest end within that prefix.
+    let furthest_end: Vec<f64> = sorted
+        .iter()
+        .scan(f64::NEG_INFINITY, |furthest, o| {
+            *furthest = furthest.max(o.1);
+            Some(*furthest)
+        })
+        .collect();
+    let is_clear = |from: f64, to: f64| {
+        let count = sorted.partition_point(|o| o.0 < to);
+        count == 0 || furthest_end[count - 1] <= from
+    };
+
+    for pair in sorted.windows(2) {
+        let (p, q) = (pair[0], pair[1]);
+        let gap = q.0 - p.1;
+        if gap <= 0.0 {
+            continue;
+        }
+        // After the pair: p, q, dragged
+        let new_start = q.1 + gap;
+        if is_clear(q.1, new_start) {
+            consider(new_start - start, vec![(p.1, q.0), (q.1, new_start)]);
+        }
+        // Before the pair: dragged, p, q
+        let new_end = p.0 - gap;
+        if is_clear(new_end, p.0) {
+            consider(new_end - end, vec![(new_end, p.0), (p.1, q.0)]);
+        }
+        // Between the pair: p, dragged, q
+        let middle_gap = (gap - len) / 2.0;
+        if middle_gap > 0.0 {
+            let new_start = p.1 + middle_gap;
+            if is_clear(p.1, q.0) || (start < q.0 && end > p.1) {
+                consider(
+                    new_start - start,
+                    vec![(p.1, new_start), (new_start + len, q.0)],
+                );
+            }
+        }
+    }
+    best
+}
+
+/// Calculate how far to pan the viewport this frame when the pointer is near the edge
+/// of the canvas bounds (left, top, width, height). The speed ramps up towards the edge.
+pub fn get_auto_pan_velocity(
+    pointer: Position,
+    bounds: (f64, f64, f64, f64),
+    edge_zone: f64,
+    speed: f64,
+) -> (f64, f64) {
+    let (left, top, width, height) = bounds;
+    let axis = |value: f64, min: f64, max: f64| -> f64 {
+        if edge_zone <= 0.0 {
+            0.0
+        } else if value < min + edge_zone {
+            // Pan towards the content beyond the near edge
+            speed * ((min + edge_zone - value) / edge_zone).min(1.0)
+        } else if value > max - edge_zone {
+            -speed * ((value - (max - edge_zone)) / edge_zone).min(1.0)
+        } else {
+            0.0
+        }
+    };
+    (
+        axis(pointer.x, left, left + width),
+        axis(pointer.y, top, top + height),
+    )
+}
+
 /// Clamp a value between min and max.
 pub fn clamp(value: f64, min: f64, max: f64) -> f64 {
     value.max(min).min(max)